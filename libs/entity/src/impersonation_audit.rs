@@ -0,0 +1,83 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Copy, Clone, Default, Debug, DeriveEntity)]
+pub struct Entity;
+
+impl EntityName for Entity {
+    fn table_name(&self) -> &str {
+        "impersonation_audit"
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, DeriveModel, DeriveActiveModel, Eq, Serialize, Deserialize)]
+pub struct Model {
+    pub id: Uuid,
+    pub admin_id: Uuid,
+    pub target_user_id: Uuid,
+    pub reason: Option<String>,
+    pub started_at: DateTimeWithTimeZone,
+    pub expires_at: DateTimeWithTimeZone,
+    pub ended_at: Option<DateTimeWithTimeZone>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveColumn)]
+pub enum Column {
+    Id,
+    AdminId,
+    TargetUserId,
+    Reason,
+    StartedAt,
+    ExpiresAt,
+    EndedAt,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DerivePrimaryKey)]
+pub enum PrimaryKey {
+    Id,
+}
+
+impl PrimaryKeyTrait for PrimaryKey {
+    type ValueType = Uuid;
+    fn auto_increment() -> bool {
+        false
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter)]
+pub enum Relation {
+    Admin,
+    TargetUser,
+}
+
+impl ColumnTrait for Column {
+    type EntityName = Entity;
+    fn def(&self) -> ColumnDef {
+        match self {
+            Self::Id => ColumnType::Uuid.def(),
+            Self::AdminId => ColumnType::Uuid.def(),
+            Self::TargetUserId => ColumnType::Uuid.def(),
+            Self::Reason => ColumnType::Text.def().null(),
+            Self::StartedAt => ColumnType::TimestampWithTimeZone.def(),
+            Self::ExpiresAt => ColumnType::TimestampWithTimeZone.def(),
+            Self::EndedAt => ColumnType::TimestampWithTimeZone.def().null(),
+        }
+    }
+}
+
+impl RelationTrait for Relation {
+    fn def(&self) -> RelationDef {
+        match self {
+            Self::Admin => Entity::belongs_to(super::user_account::Entity)
+                .from(Column::AdminId)
+                .to(super::user_account::Column::Id)
+                .into(),
+            Self::TargetUser => Entity::belongs_to(super::user_account::Entity)
+                .from(Column::TargetUserId)
+                .to(super::user_account::Column::Id)
+                .into(),
+        }
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
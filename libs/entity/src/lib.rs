@@ -1,3 +1,7 @@
 pub mod prelude;
 
-pub mod user_account;
\ No newline at end of file
+pub mod data_export;
+pub mod impersonation_audit;
+pub mod user_account;
+pub mod user_invitation;
+pub mod user_session;
\ No newline at end of file
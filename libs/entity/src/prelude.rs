@@ -1,3 +1,7 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.3
 
+pub use super::data_export::Entity as DataExport;
+pub use super::impersonation_audit::Entity as ImpersonationAudit;
 pub use super::user_account::Entity as UserAccount;
+pub use super::user_invitation::Entity as UserInvitation;
+pub use super::user_session::Entity as UserSession;
@@ -19,6 +19,16 @@ pub struct Model {
     pub password: String,
     pub created_at: DateTimeWithTimeZone,
     pub deleted_at: Option<DateTimeWithTimeZone>,
+    pub permissions: Json,
+    pub disabled_at: Option<DateTimeWithTimeZone>,
+    pub display_name: Option<String>,
+    pub locale: Option<String>,
+    pub timezone: Option<String>,
+    pub avatar_key: Option<String>,
+    pub phone: Option<String>,
+    pub accent_color: Option<String>,
+    pub map_extent: Option<Json>,
+    pub location: Option<Json>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveColumn)]
@@ -28,6 +38,16 @@ pub enum Column {
     Password,
     CreatedAt,
     DeletedAt,
+    Permissions,
+    DisabledAt,
+    DisplayName,
+    Locale,
+    Timezone,
+    AvatarKey,
+    Phone,
+    AccentColor,
+    MapExtent,
+    Location,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DerivePrimaryKey)]
@@ -44,10 +64,12 @@ impl PrimaryKeyTrait for PrimaryKey {
 
 #[derive(Copy, Clone, Debug, EnumIter)]
 pub enum Relation {
+    DataExport,
     Project,
     ProjectData,
     ProjectDataImage,
     ProjectParticipant,
+    UserSession,
 }
 
 impl ColumnTrait for Column {
@@ -59,6 +81,16 @@ impl ColumnTrait for Column {
             Self::Password => ColumnType::Text.def(),
             Self::CreatedAt => ColumnType::TimestampWithTimeZone.def(),
             Self::DeletedAt => ColumnType::TimestampWithTimeZone.def().null(),
+            Self::Permissions => ColumnType::Json.def(),
+            Self::DisabledAt => ColumnType::TimestampWithTimeZone.def().null(),
+            Self::DisplayName => ColumnType::Text.def().null(),
+            Self::Locale => ColumnType::Text.def().null(),
+            Self::Timezone => ColumnType::Text.def().null(),
+            Self::AvatarKey => ColumnType::Text.def().null(),
+            Self::Phone => ColumnType::Text.def().null(),
+            Self::AccentColor => ColumnType::Text.def().null(),
+            Self::MapExtent => ColumnType::Json.def().null(),
+            Self::Location => ColumnType::Json.def().null(),
         }
     }
 }
@@ -66,14 +98,22 @@ impl ColumnTrait for Column {
 impl RelationTrait for Relation {
     fn def(&self) -> RelationDef {
         match self {
+            Self::DataExport => Entity::has_many(super::data_export::Entity).into(),
             Self::Project => Entity::has_many(super::project::Entity).into(),
             Self::ProjectData => Entity::has_many(super::project_data::Entity).into(),
             Self::ProjectDataImage => Entity::has_many(super::project_data_image::Entity).into(),
             Self::ProjectParticipant => Entity::has_many(super::project_participant::Entity).into(),
+            Self::UserSession => Entity::has_many(super::user_session::Entity).into(),
         }
     }
 }
 
+impl Related<super::data_export::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::DataExport.def()
+    }
+}
+
 impl Related<super::project::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Project.def()
@@ -98,4 +138,10 @@ impl Related<super::project_participant::Entity> for Entity {
     }
 }
 
+impl Related<super::user_session::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::UserSession.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,101 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Copy, Clone, Default, Debug, DeriveEntity)]
+pub struct Entity;
+
+impl EntityName for Entity {
+    fn table_name(&self) -> &str {
+        "user_invitation"
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, DeriveModel, DeriveActiveModel, Eq, Serialize, Deserialize)]
+pub struct Model {
+    pub id: Uuid,
+    pub email: String,
+    pub token_hash: String,
+    pub permissions: Json,
+    pub project_id: Option<Uuid>,
+    pub invited_by: Uuid,
+    pub sent_count: i32,
+    pub last_sent_at: DateTimeWithTimeZone,
+    pub created_at: DateTimeWithTimeZone,
+    pub expires_at: DateTimeWithTimeZone,
+    pub accepted_at: Option<DateTimeWithTimeZone>,
+    pub accepted_user_id: Option<Uuid>,
+    pub revoked_at: Option<DateTimeWithTimeZone>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveColumn)]
+pub enum Column {
+    Id,
+    Email,
+    TokenHash,
+    Permissions,
+    ProjectId,
+    InvitedBy,
+    SentCount,
+    LastSentAt,
+    CreatedAt,
+    ExpiresAt,
+    AcceptedAt,
+    AcceptedUserId,
+    RevokedAt,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DerivePrimaryKey)]
+pub enum PrimaryKey {
+    Id,
+}
+
+impl PrimaryKeyTrait for PrimaryKey {
+    type ValueType = Uuid;
+    fn auto_increment() -> bool {
+        false
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter)]
+pub enum Relation {
+    Inviter,
+    AcceptedUser,
+}
+
+impl ColumnTrait for Column {
+    type EntityName = Entity;
+    fn def(&self) -> ColumnDef {
+        match self {
+            Self::Id => ColumnType::Uuid.def(),
+            Self::Email => ColumnType::Text.def(),
+            Self::TokenHash => ColumnType::Text.def().unique(),
+            Self::Permissions => ColumnType::Json.def(),
+            Self::ProjectId => ColumnType::Uuid.def().null(),
+            Self::InvitedBy => ColumnType::Uuid.def(),
+            Self::SentCount => ColumnType::Integer.def(),
+            Self::LastSentAt => ColumnType::TimestampWithTimeZone.def(),
+            Self::CreatedAt => ColumnType::TimestampWithTimeZone.def(),
+            Self::ExpiresAt => ColumnType::TimestampWithTimeZone.def(),
+            Self::AcceptedAt => ColumnType::TimestampWithTimeZone.def().null(),
+            Self::AcceptedUserId => ColumnType::Uuid.def().null(),
+            Self::RevokedAt => ColumnType::TimestampWithTimeZone.def().null(),
+        }
+    }
+}
+
+impl RelationTrait for Relation {
+    fn def(&self) -> RelationDef {
+        match self {
+            Self::Inviter => Entity::belongs_to(super::user_account::Entity)
+                .from(Column::InvitedBy)
+                .to(super::user_account::Column::Id)
+                .into(),
+            Self::AcceptedUser => Entity::belongs_to(super::user_account::Entity)
+                .from(Column::AcceptedUserId)
+                .to(super::user_account::Column::Id)
+                .into(),
+        }
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
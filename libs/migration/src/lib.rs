@@ -1,10 +1,32 @@
 pub use sea_orm_migration::prelude::*;
 
+mod m20261018_000001_add_user_permissions;
+mod m20261018_000002_create_impersonation_audit;
+mod m20261018_000003_create_user_session;
+mod m20261018_000004_add_user_disabled_at;
+mod m20261018_000005_add_user_profile;
+mod m20261018_000006_create_data_export;
+mod m20261018_000007_create_user_invitation;
+mod m20261019_000008_add_user_username_unique;
+mod m20261019_000009_add_data_export_in_progress_unique;
+mod m20261019_000010_add_user_contact_and_map;
+
 pub struct Migrator;
 
 #[async_trait::async_trait]
 impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
-        vec![]
+        vec![
+            Box::new(m20261018_000001_add_user_permissions::Migration),
+            Box::new(m20261018_000002_create_impersonation_audit::Migration),
+            Box::new(m20261018_000003_create_user_session::Migration),
+            Box::new(m20261018_000004_add_user_disabled_at::Migration),
+            Box::new(m20261018_000005_add_user_profile::Migration),
+            Box::new(m20261018_000006_create_data_export::Migration),
+            Box::new(m20261018_000007_create_user_invitation::Migration),
+            Box::new(m20261019_000008_add_user_username_unique::Migration),
+            Box::new(m20261019_000009_add_data_export_in_progress_unique::Migration),
+            Box::new(m20261019_000010_add_user_contact_and_map::Migration),
+        ]
     }
 }
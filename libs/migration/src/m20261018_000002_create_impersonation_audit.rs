@@ -0,0 +1,68 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ImpersonationAudit::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(ImpersonationAudit::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(ImpersonationAudit::AdminId).uuid().not_null())
+                    .col(ColumnDef::new(ImpersonationAudit::TargetUserId).uuid().not_null())
+                    .col(ColumnDef::new(ImpersonationAudit::Reason).text())
+                    .col(
+                        ColumnDef::new(ImpersonationAudit::StartedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(ImpersonationAudit::ExpiresAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ImpersonationAudit::EndedAt).timestamp_with_time_zone())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(ImpersonationAudit::Table, ImpersonationAudit::AdminId)
+                            .to(UserAccount::Table, UserAccount::Id),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(ImpersonationAudit::Table, ImpersonationAudit::TargetUserId)
+                            .to(UserAccount::Table, UserAccount::Id),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ImpersonationAudit::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ImpersonationAudit {
+    Table,
+    Id,
+    AdminId,
+    TargetUserId,
+    Reason,
+    StartedAt,
+    ExpiresAt,
+    EndedAt,
+}
+
+#[derive(DeriveIden)]
+enum UserAccount {
+    Table,
+    Id,
+}
@@ -0,0 +1,52 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+const PROFILE_COLUMNS: [UserAccount; 4] = [
+    UserAccount::DisplayName,
+    UserAccount::Locale,
+    UserAccount::Timezone,
+    UserAccount::AvatarKey,
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite only accepts a single column per ALTER TABLE
+        for column in PROFILE_COLUMNS {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(UserAccount::Table)
+                        .add_column(ColumnDef::new(column).text())
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in PROFILE_COLUMNS {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(UserAccount::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden, Clone, Copy)]
+enum UserAccount {
+    Table,
+    DisplayName,
+    Locale,
+    Timezone,
+    AvatarKey,
+}
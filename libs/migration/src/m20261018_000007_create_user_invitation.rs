@@ -0,0 +1,111 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserInvitation::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(UserInvitation::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(UserInvitation::Email).text().not_null())
+                    .col(
+                        ColumnDef::new(UserInvitation::TokenHash)
+                            .text()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(
+                        ColumnDef::new(UserInvitation::Permissions)
+                            .json()
+                            .not_null()
+                            .default("[]"),
+                    )
+                    .col(ColumnDef::new(UserInvitation::ProjectId).uuid())
+                    .col(ColumnDef::new(UserInvitation::InvitedBy).uuid().not_null())
+                    .col(
+                        ColumnDef::new(UserInvitation::SentCount)
+                            .integer()
+                            .not_null()
+                            .default(1),
+                    )
+                    .col(
+                        ColumnDef::new(UserInvitation::LastSentAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(UserInvitation::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(UserInvitation::ExpiresAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(UserInvitation::AcceptedAt).timestamp_with_time_zone())
+                    .col(ColumnDef::new(UserInvitation::AcceptedUserId).uuid())
+                    .col(ColumnDef::new(UserInvitation::RevokedAt).timestamp_with_time_zone())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(UserInvitation::Table, UserInvitation::InvitedBy)
+                            .to(UserAccount::Table, UserAccount::Id),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(UserInvitation::Table, UserInvitation::AcceptedUserId)
+                            .to(UserAccount::Table, UserAccount::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_user_invitation_email")
+                    .table(UserInvitation::Table)
+                    .col(UserInvitation::Email)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(UserInvitation::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum UserInvitation {
+    Table,
+    Id,
+    Email,
+    TokenHash,
+    Permissions,
+    ProjectId,
+    InvitedBy,
+    SentCount,
+    LastSentAt,
+    CreatedAt,
+    ExpiresAt,
+    AcceptedAt,
+    AcceptedUserId,
+    RevokedAt,
+}
+
+#[derive(DeriveIden)]
+enum UserAccount {
+    Table,
+    Id,
+}
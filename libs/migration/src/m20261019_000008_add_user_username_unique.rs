@@ -0,0 +1,62 @@
+use sea_orm_migration::prelude::*;
+
+/// Usernames are checked for conflicts by this index only, named like the PostgreSQL default
+/// `UNIQUE` constraint so violations report the `username` column.
+///
+/// Rows written before the index may already share a username. Which account keeps it is not
+/// for a migration to decide, so it fails naming them until all but one are renamed or removed.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let duplicates = Query::select()
+            .column(UserAccount::Username)
+            .from(UserAccount::Table)
+            .group_by_col(UserAccount::Username)
+            .and_having(Expr::col(UserAccount::Username).count().gt(1))
+            .to_owned();
+        let db = manager.get_connection();
+        let rows = db.query_all(db.get_database_backend().build(&duplicates)).await?;
+        if !rows.is_empty() {
+            let usernames = rows
+                .iter()
+                .map(|row| row.try_get::<String>("", "username"))
+                .collect::<Result<Vec<_>, _>>()?;
+            return Err(DbErr::Migration(format!(
+                "usernames shared by several accounts, rename or remove all but one of each: {}",
+                usernames.join(", ")
+            )));
+        }
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("user_account_username_key")
+                    .table(UserAccount::Table)
+                    .col(UserAccount::Username)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("user_account_username_key")
+                    .table(UserAccount::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum UserAccount {
+    Table,
+    Username,
+}
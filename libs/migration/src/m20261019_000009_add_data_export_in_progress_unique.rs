@@ -0,0 +1,58 @@
+use sea_orm_migration::prelude::*;
+
+/// At most one export per user may be pending or running, enforced by a partial unique index
+/// so concurrent requests cannot both queue one.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Exports in progress were interrupted by the restart running this migration, failing
+        // them as the app does at startup also clears duplicates the index would reject
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(DataExport::Table)
+                    .value(DataExport::Status, "failed")
+                    .value(DataExport::Error, "export could not be generated, please try again")
+                    .value(DataExport::CompletedAt, Expr::current_timestamp())
+                    .and_where(Expr::col(DataExport::Status).is_in(["pending", "running"]))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_data_export_user_in_progress")
+                    .table(DataExport::Table)
+                    .col(DataExport::UserId)
+                    .unique()
+                    .and_where(Expr::col(DataExport::Status).is_in(["pending", "running"]))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_data_export_user_in_progress")
+                    .table(DataExport::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum DataExport {
+    Table,
+    UserId,
+    Status,
+    Error,
+    CompletedAt,
+}
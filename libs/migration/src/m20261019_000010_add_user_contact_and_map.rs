@@ -0,0 +1,60 @@
+use sea_orm_migration::prelude::*;
+
+/// Phone number, accent color and map preferences of the profile. The extent is a GeoJSON
+/// bounding box and the location a GeoJSON geometry, both stored as JSON.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+const COLUMNS: [UserAccount; 4] = [
+    UserAccount::Phone,
+    UserAccount::AccentColor,
+    UserAccount::MapExtent,
+    UserAccount::Location,
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let mut columns = [
+            ColumnDef::new(UserAccount::Phone).text().to_owned(),
+            ColumnDef::new(UserAccount::AccentColor).text().to_owned(),
+            ColumnDef::new(UserAccount::MapExtent).json().to_owned(),
+            ColumnDef::new(UserAccount::Location).json().to_owned(),
+        ];
+        // SQLite only accepts a single column per ALTER TABLE
+        for column in &mut columns {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(UserAccount::Table)
+                        .add_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in COLUMNS {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(UserAccount::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden, Clone, Copy)]
+enum UserAccount {
+    Table,
+    Phone,
+    AccentColor,
+    MapExtent,
+    Location,
+}
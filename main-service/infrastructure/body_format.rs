@@ -0,0 +1,177 @@
+use axum::http::header::{ACCEPT, CONTENT_TYPE};
+use axum::http::HeaderMap;
+use serde_json::Value;
+
+pub const MSGPACK: &str = "application/msgpack";
+pub const CBOR: &str = "application/cbor";
+
+/// Largest request body accepted, in any format.
+pub const MAX_BODY_SIZE: usize = 15 * 1024 * 1024;
+
+/// Wire format of request and response bodies. Handlers only ever see JSON, the
+/// `body_format` middleware converts the binary formats on the way in and out.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BodyFormat {
+    #[default]
+    Json,
+    MessagePack,
+    Cbor,
+}
+
+impl BodyFormat {
+    pub fn media_type(self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::MessagePack => MSGPACK,
+            Self::Cbor => CBOR,
+        }
+    }
+
+    fn of_media_type(media_type: &str) -> Option<Self> {
+        match media_type.to_ascii_lowercase().as_str() {
+            "application/json" => Some(Self::Json),
+            // Older clients still send the unregistered names
+            MSGPACK | "application/x-msgpack" | "application/vnd.msgpack" => Some(Self::MessagePack),
+            CBOR => Some(Self::Cbor),
+            _ => None,
+        }
+    }
+
+    /// Format of the request body, `None` for anything that is not a body format.
+    pub fn of_content_type(headers: &HeaderMap) -> Option<Self> {
+        let content_type = headers.get(CONTENT_TYPE)?.to_str().ok()?;
+        let media_type = content_type.split(';').next().unwrap_or_default().trim();
+        Self::of_media_type(media_type)
+    }
+
+    /// The format listed in `Accept` with the highest quality, the first one on ties.
+    /// Falls back to JSON, also for wildcards.
+    pub fn negotiate(headers: &HeaderMap) -> Self {
+        let mut best: Option<(Self, f32)> = None;
+        for (media_type, quality) in media_ranges(headers) {
+            let Some(format) = Self::of_media_type(&media_type) else {
+                continue;
+            };
+            if quality > 0.0 && best.is_none_or(|(_, best)| quality > best) {
+                best = Some((format, quality));
+            }
+        }
+        best.map(|(format, _)| format).unwrap_or_default()
+    }
+
+    pub fn decode(self, bytes: &[u8]) -> Result<Value, String> {
+        match self {
+            Self::Json => serde_json::from_slice(bytes).map_err(|e| e.to_string()),
+            Self::MessagePack => rmp_serde::from_slice(bytes).map_err(|e| e.to_string()),
+            Self::Cbor => ciborium::from_reader(bytes).map_err(|e| e.to_string()),
+        }
+    }
+
+    pub fn encode(self, value: &Value) -> Result<Vec<u8>, String> {
+        match self {
+            Self::Json => serde_json::to_vec(value).map_err(|e| e.to_string()),
+            Self::MessagePack => rmp_serde::to_vec_named(value).map_err(|e| e.to_string()),
+            Self::Cbor => {
+                let mut bytes = Vec::new();
+                ciborium::into_writer(value, &mut bytes).map_err(|e| e.to_string())?;
+                Ok(bytes)
+            }
+        }
+    }
+}
+
+/// Media ranges of the `Accept` headers with their quality, in the order they were sent.
+pub fn media_ranges(headers: &HeaderMap) -> Vec<(String, f32)> {
+    headers
+        .get_all(ACCEPT)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|range| {
+            let mut params = range.split(';').map(str::trim);
+            let media_type = params.next().unwrap_or_default().to_string();
+            let quality = params
+                .find_map(|param| param.strip_prefix("q="))
+                .and_then(|q| q.parse::<f32>().ok())
+                .unwrap_or(1.0);
+            (media_type, quality)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BodyFormat;
+    use axum::http::header::{ACCEPT, CONTENT_TYPE};
+    use axum::http::{HeaderMap, HeaderValue};
+    use serde_json::json;
+
+    fn headers(name: axum::http::HeaderName, values: &[&'static str]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for value in values {
+            headers.append(name.clone(), HeaderValue::from_static(value));
+        }
+        headers
+    }
+
+    fn negotiate(accept: &[&'static str]) -> BodyFormat {
+        BodyFormat::negotiate(&headers(ACCEPT, accept))
+    }
+
+    #[test]
+    fn negotiate_picks_the_highest_quality() {
+        assert_eq!(negotiate(&[]), BodyFormat::Json);
+        assert_eq!(negotiate(&["application/cbor"]), BodyFormat::Cbor);
+        assert_eq!(
+            negotiate(&["application/json;q=0.5, application/msgpack;q=0.9"]),
+            BodyFormat::MessagePack
+        );
+        // Ties go to the first listed, also across several headers
+        assert_eq!(negotiate(&["application/cbor", "application/msgpack"]), BodyFormat::Cbor);
+        assert_eq!(negotiate(&["application/msgpack;q=0, application/cbor;q=0.1"]), BodyFormat::Cbor);
+    }
+
+    #[test]
+    fn negotiate_falls_back_to_json() {
+        assert_eq!(negotiate(&["*/*"]), BodyFormat::Json);
+        assert_eq!(negotiate(&["text/html, application/*"]), BodyFormat::Json);
+        assert_eq!(negotiate(&["application/cbor;q=0"]), BodyFormat::Json);
+        assert_eq!(negotiate(&["application/cbor;q=x"]), BodyFormat::Cbor);
+    }
+
+    #[test]
+    fn content_type_accepts_legacy_names_and_parameters() {
+        let of = |value| BodyFormat::of_content_type(&headers(CONTENT_TYPE, &[value]));
+        assert_eq!(of("application/x-msgpack"), Some(BodyFormat::MessagePack));
+        assert_eq!(of("Application/VND.MsgPack"), Some(BodyFormat::MessagePack));
+        assert_eq!(of("application/json; charset=utf-8"), Some(BodyFormat::Json));
+        assert_eq!(of("text/plain"), None);
+        assert_eq!(BodyFormat::of_content_type(&HeaderMap::new()), None);
+    }
+
+    #[test]
+    fn binary_formats_round_trip() {
+        let value = json!({
+            "name": "Zoë",
+            "count": 42,
+            "ratio": -0.25,
+            "flags": [true, false, null],
+            "nested": { "empty": {}, "list": [] },
+        });
+        for format in [BodyFormat::Json, BodyFormat::MessagePack, BodyFormat::Cbor] {
+            let bytes = format.encode(&value).unwrap();
+            assert_eq!(format.decode(&bytes).unwrap(), value, "{format:?}");
+        }
+        // Field names survive, so handlers see the same JSON object
+        let bytes = BodyFormat::MessagePack.encode(&value).unwrap();
+        assert!(bytes.windows(5).any(|window| window == b"ratio"));
+    }
+
+    #[test]
+    fn decode_rejects_truncated_bodies() {
+        for format in [BodyFormat::MessagePack, BodyFormat::Cbor] {
+            let bytes = format.encode(&json!({ "name": "value" })).unwrap();
+            assert!(format.decode(&bytes[..bytes.len() - 1]).is_err(), "{format:?}");
+        }
+    }
+}
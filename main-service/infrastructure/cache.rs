@@ -0,0 +1,180 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use entity::user_account;
+use moka::future::Cache;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::infrastructure::errors::AppResult;
+
+/// Short lived cache of authenticated users keyed by user id.
+///
+/// Entries must be invalidated whenever the user row changes, see
+/// [`UserPrincipalCache::invalidate`].
+#[derive(Debug)]
+pub struct UserPrincipalCache {
+    cache: Cache<Uuid, user_account::Model>,
+    /// Bumped by every invalidation, loads that overlap one are not kept.
+    generation: AtomicU64,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub hit_rate: f64,
+    pub entries: u64,
+}
+
+impl UserPrincipalCache {
+    pub fn new(max_capacity: u64, ttl: Duration) -> Self {
+        Self {
+            cache: Cache::builder()
+                .max_capacity(max_capacity)
+                .time_to_live(ttl)
+                .build(),
+            generation: AtomicU64::new(0),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the cached user or runs `load` and caches its result.
+    ///
+    /// A load may read the row just before a write whose invalidation lands before the insert.
+    /// Such a stale row would authenticate a disabled or deleted user until the TTL, so it is
+    /// dropped again when any invalidation happened while loading.
+    pub async fn get_or_load<F>(&self, user_id: Uuid, load: F) -> AppResult<user_account::Model>
+    where
+        F: std::future::Future<Output = AppResult<user_account::Model>>,
+    {
+        if let Some(user) = self.cache.get(&user_id).await {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(user);
+        }
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let generation = self.generation.load(Ordering::Acquire);
+        let user = load.await?;
+        self.cache.insert(user_id, user.clone()).await;
+        // Checked after the insert, an invalidation racing the check removes the entry itself
+        if self.generation.load(Ordering::Acquire) != generation {
+            self.cache.invalidate(&user_id).await;
+        }
+        Ok(user)
+    }
+
+    pub async fn invalidate(&self, user_id: &Uuid) {
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        self.cache.invalidate(user_id).await;
+    }
+
+    pub fn invalidate_all(&self) {
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        self.cache.invalidate_all();
+    }
+
+    /// Log the cache statistics at info level every `period` until the cache is dropped.
+    pub fn report_stats(self: &Arc<Self>, period: Duration) {
+        let cache: Weak<Self> = Arc::downgrade(self);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            // The first tick completes immediately, skip it so an empty cache is not reported
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                let Some(cache) = cache.upgrade() else {
+                    break;
+                };
+                let stats = cache.stats();
+                tracing::info!(
+                    hits = stats.hits,
+                    misses = stats.misses,
+                    hit_rate = stats.hit_rate,
+                    entries = stats.entries,
+                    "user principal cache stats"
+                );
+            }
+        });
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        let hits = self.hits.load(Ordering::Relaxed);
+        let misses = self.misses.load(Ordering::Relaxed);
+        let total = hits + misses;
+        CacheStats {
+            hits,
+            misses,
+            hit_rate: if total == 0 { 0.0 } else { hits as f64 / total as f64 },
+            entries: self.cache.entry_count(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UserPrincipalCache;
+    use entity::user_account;
+    use std::time::Duration;
+    use tokio::sync::oneshot;
+    use uuid::Uuid;
+
+    fn user(id: Uuid, username: &str) -> user_account::Model {
+        user_account::Model {
+            id,
+            username: username.to_string(),
+            password: String::new(),
+            created_at: chrono::Utc::now().fixed_offset(),
+            deleted_at: None,
+            permissions: serde_json::json!([]),
+            disabled_at: None,
+            display_name: None,
+            locale: None,
+            timezone: None,
+            avatar_key: None,
+            phone: None,
+            accent_color: None,
+            map_extent: None,
+            location: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn loads_are_cached() {
+        let cache = UserPrincipalCache::new(10, Duration::from_secs(60));
+        let id = Uuid::now_v7();
+        cache.get_or_load(id, async { Ok(user(id, "first")) }).await.unwrap();
+        let cached = cache.get_or_load(id, async { Ok(user(id, "second")) }).await.unwrap();
+        assert_eq!(cached.username, "first");
+        assert_eq!((cache.stats().hits, cache.stats().misses), (1, 1));
+    }
+
+    #[tokio::test]
+    async fn loads_overlapping_an_invalidation_are_not_cached() {
+        let cache = UserPrincipalCache::new(10, Duration::from_secs(60));
+        let id = Uuid::now_v7();
+        let (read, row_read) = oneshot::channel();
+        let (written, write_done) = oneshot::channel::<()>();
+        let load = cache.get_or_load(id, async move {
+            // The row is read before the write commits and invalidates
+            let stale = user(id, "stale");
+            read.send(()).unwrap();
+            write_done.await.unwrap();
+            Ok(stale)
+        });
+        let write = async {
+            row_read.await.unwrap();
+            cache.invalidate(&id).await;
+            written.send(()).unwrap();
+        };
+        let (stale, ()) = tokio::join!(load, write);
+        assert_eq!(stale.unwrap().username, "stale");
+
+        let fresh = cache.get_or_load(id, async { Ok(user(id, "fresh")) }).await.unwrap();
+        assert_eq!(fresh.username, "fresh");
+    }
+}
@@ -0,0 +1,384 @@
+use crate::infrastructure::error_code::ErrorCode;
+use sea_orm::sqlx::error::{DatabaseError, ErrorKind};
+use sea_orm::{DbErr, RuntimeErr};
+
+/// PostgreSQL SQLSTATE codes and classes worth retrying once the database is reachable again.
+const PG_UNAVAILABLE_CLASSES: [&str; 1] = ["08"];
+const PG_UNAVAILABLE_CODES: [&str; 7] = [
+    "53300", // too_many_connections
+    "57014", // query_canceled, raised by statement_timeout
+    "57P01", // admin_shutdown
+    "57P02", // crash_shutdown
+    "57P03", // cannot_connect_now
+    "40001", // serialization_failure
+    "40P01", // deadlock_detected
+];
+/// SQLite (extended) result codes for a locked or busy database file.
+const SQLITE_UNAVAILABLE_CODES: [&str; 5] = [
+    "5",   // SQLITE_BUSY
+    "6",   // SQLITE_LOCKED
+    "261", // SQLITE_BUSY_RECOVERY
+    "262", // SQLITE_LOCKED_SHAREDCACHE
+    "517", // SQLITE_BUSY_SNAPSHOT
+];
+
+/// Backend independent cause of a failed database call.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DbFailure {
+    UniqueViolation { field: Option<String> },
+    /// `still_referenced` is `Some(true)` when deleting a row other rows point at and
+    /// `Some(false)` when the referenced row does not exist. SQLite does not tell.
+    ForeignKeyViolation { field: Option<String>, still_referenced: Option<bool> },
+    NotNullViolation { field: Option<String> },
+    CheckViolation { field: Option<String> },
+    NotFound,
+    /// Connection failures, pool exhaustion, timeouts and lock contention.
+    Unavailable,
+    Other,
+}
+
+impl DbFailure {
+    pub fn of(err: &DbErr) -> Self {
+        match err {
+            DbErr::ConnectionAcquire(_) => DbFailure::Unavailable,
+            DbErr::Conn(RuntimeErr::SqlxError(e))
+            | DbErr::Exec(RuntimeErr::SqlxError(e))
+            | DbErr::Query(RuntimeErr::SqlxError(e)) => Self::of_sqlx(e),
+            DbErr::Conn(_) => DbFailure::Unavailable,
+            DbErr::RecordNotFound(_) | DbErr::RecordNotUpdated => DbFailure::NotFound,
+            _ => DbFailure::Other,
+        }
+    }
+
+    pub fn of_sqlx(err: &sea_orm::sqlx::Error) -> Self {
+        use sea_orm::sqlx::Error;
+        match err {
+            Error::Database(e) => Self::of_database(e.as_ref()),
+            Error::RowNotFound => DbFailure::NotFound,
+            Error::PoolTimedOut
+            | Error::PoolClosed
+            | Error::WorkerCrashed
+            | Error::Io(_)
+            | Error::Tls(_) => DbFailure::Unavailable,
+            _ => DbFailure::Other,
+        }
+    }
+
+    fn of_database(err: &dyn DatabaseError) -> Self {
+        match err.kind() {
+            ErrorKind::UniqueViolation => DbFailure::UniqueViolation {
+                field: Self::field_of(err),
+            },
+            ErrorKind::ForeignKeyViolation => DbFailure::ForeignKeyViolation {
+                field: Self::field_of(err),
+                still_referenced: Self::still_referenced(err.message()),
+            },
+            ErrorKind::NotNullViolation => DbFailure::NotNullViolation {
+                field: Self::field_of(err),
+            },
+            ErrorKind::CheckViolation => DbFailure::CheckViolation {
+                field: Self::field_of(err),
+            },
+            _ => match err.code() {
+                Some(code) if Self::is_unavailable_code(&code) => DbFailure::Unavailable,
+                _ => DbFailure::Other,
+            },
+        }
+    }
+
+    /// Code sent to the client, `None` when the failure is not worth telling apart.
+    pub fn code(&self) -> Option<ErrorCode> {
+        match self {
+            DbFailure::UniqueViolation { .. } => Some(ErrorCode::DB_UNIQUE_VIOLATION),
+            DbFailure::ForeignKeyViolation { .. } => Some(ErrorCode::DB_FOREIGN_KEY_VIOLATION),
+            DbFailure::NotNullViolation { .. } => Some(ErrorCode::DB_NOT_NULL_VIOLATION),
+            DbFailure::CheckViolation { .. } => Some(ErrorCode::DB_CHECK_VIOLATION),
+            DbFailure::NotFound => Some(ErrorCode::DB_RECORD_NOT_FOUND),
+            DbFailure::Unavailable => Some(ErrorCode::DB_UNAVAILABLE),
+            DbFailure::Other => None,
+        }
+    }
+
+    /// PostgreSQL: `insert or update on table ...` or `update or delete on table ...`.
+    fn still_referenced(message: &str) -> Option<bool> {
+        if message.starts_with("update or delete") {
+            Some(true)
+        } else if message.starts_with("insert or update") {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    fn is_unavailable_code(code: &str) -> bool {
+        PG_UNAVAILABLE_CLASSES.iter().any(|class| code.starts_with(class))
+            || PG_UNAVAILABLE_CODES.contains(&code)
+            || SQLITE_UNAVAILABLE_CODES.contains(&code)
+    }
+
+    /// Best effort name of the offending column, taken from the message or the constraint.
+    fn field_of(err: &dyn DatabaseError) -> Option<String> {
+        Self::raw_field_of(err).filter(|field| {
+            // Unnamed SQLite checks report their expression instead of a name
+            !field.is_empty() && field.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        })
+    }
+
+    fn raw_field_of(err: &dyn DatabaseError) -> Option<String> {
+        let message = err.message();
+        // SQLite: `UNIQUE constraint failed: user_account.username`
+        if let Some((_, columns)) = message.split_once("constraint failed: ") {
+            let column = columns.split(',').next()?.trim();
+            return column.rsplit('.').next().map(str::to_string);
+        }
+        // PostgreSQL: `null value in column "username" of relation "user_account" ...`
+        if let Some((_, rest)) = message.split_once("column \"") {
+            return rest.split('"').next().map(str::to_string);
+        }
+        // PostgreSQL default constraint names: `{table}_{column}_key`, `..._fkey`, `..._check`
+        let constraint = err.constraint()?;
+        let name = err
+            .table()
+            .and_then(|table| constraint.strip_prefix(table))
+            .unwrap_or(constraint);
+        let name = ["_pkey", "_key", "_fkey", "_check", "_not_null"]
+            .iter()
+            .find_map(|suffix| name.strip_suffix(suffix))
+            .unwrap_or(name);
+        // `{table}_pkey` names no column and ends up empty
+        Some(name.strip_prefix('_').unwrap_or(name).to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DbFailure;
+    use sea_orm::sqlx::error::{DatabaseError, ErrorKind};
+    use std::borrow::Cow;
+    use std::error::Error as StdError;
+    use std::fmt;
+
+    /// A driver error as SQLite or PostgreSQL report it, the real ones cannot be built outside sqlx.
+    #[derive(Debug)]
+    struct DriverError {
+        message: &'static str,
+        code: &'static str,
+        kind: ErrorKind,
+        constraint: Option<&'static str>,
+        table: Option<&'static str>,
+    }
+
+    impl fmt::Display for DriverError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(self.message)
+        }
+    }
+
+    impl StdError for DriverError {}
+
+    impl DatabaseError for DriverError {
+        fn message(&self) -> &str {
+            self.message
+        }
+
+        fn code(&self) -> Option<Cow<'_, str>> {
+            Some(Cow::Borrowed(self.code))
+        }
+
+        fn as_error(&self) -> &(dyn StdError + Send + Sync + 'static) {
+            self
+        }
+
+        fn as_error_mut(&mut self) -> &mut (dyn StdError + Send + Sync + 'static) {
+            self
+        }
+
+        fn into_error(self: Box<Self>) -> Box<dyn StdError + Send + Sync + 'static> {
+            self
+        }
+
+        fn constraint(&self) -> Option<&str> {
+            self.constraint
+        }
+
+        fn table(&self) -> Option<&str> {
+            self.table
+        }
+
+        fn kind(&self) -> ErrorKind {
+            match &self.kind {
+                ErrorKind::UniqueViolation => ErrorKind::UniqueViolation,
+                ErrorKind::ForeignKeyViolation => ErrorKind::ForeignKeyViolation,
+                ErrorKind::NotNullViolation => ErrorKind::NotNullViolation,
+                ErrorKind::CheckViolation => ErrorKind::CheckViolation,
+                _ => ErrorKind::Other,
+            }
+        }
+    }
+
+    fn sqlite(code: &'static str, kind: ErrorKind, message: &'static str) -> DriverError {
+        DriverError { message, code, kind, constraint: None, table: None }
+    }
+
+    fn postgres(
+        code: &'static str,
+        kind: ErrorKind,
+        message: &'static str,
+        table: &'static str,
+        constraint: Option<&'static str>,
+    ) -> DriverError {
+        DriverError { message, code, kind, constraint, table: Some(table) }
+    }
+
+    fn field(name: &str) -> Option<String> {
+        Some(name.to_string())
+    }
+
+    #[test]
+    fn classifies_sqlite_errors() {
+        let cases = [
+            (
+                sqlite("2067", ErrorKind::UniqueViolation, "UNIQUE constraint failed: user_account.username"),
+                DbFailure::UniqueViolation { field: field("username") },
+            ),
+            (
+                sqlite(
+                    "2067",
+                    ErrorKind::UniqueViolation,
+                    "UNIQUE constraint failed: project_participant.project_id, project_participant.user_id",
+                ),
+                DbFailure::UniqueViolation { field: field("project_id") },
+            ),
+            (
+                sqlite("1555", ErrorKind::UniqueViolation, "UNIQUE constraint failed: user_account.id"),
+                DbFailure::UniqueViolation { field: field("id") },
+            ),
+            (
+                sqlite("787", ErrorKind::ForeignKeyViolation, "FOREIGN KEY constraint failed"),
+                DbFailure::ForeignKeyViolation { field: None, still_referenced: None },
+            ),
+            (
+                sqlite("1299", ErrorKind::NotNullViolation, "NOT NULL constraint failed: user_account.password"),
+                DbFailure::NotNullViolation { field: field("password") },
+            ),
+            (
+                sqlite("275", ErrorKind::CheckViolation, "CHECK constraint failed: username_not_empty"),
+                DbFailure::CheckViolation { field: field("username_not_empty") },
+            ),
+            (
+                sqlite("275", ErrorKind::CheckViolation, "CHECK constraint failed: length(username) > 0"),
+                DbFailure::CheckViolation { field: None },
+            ),
+            (sqlite("5", ErrorKind::Other, "database is locked"), DbFailure::Unavailable),
+            (sqlite("6", ErrorKind::Other, "database table is locked"), DbFailure::Unavailable),
+            (sqlite("517", ErrorKind::Other, "database is locked"), DbFailure::Unavailable),
+            (sqlite("1", ErrorKind::Other, "no such table: user_account"), DbFailure::Other),
+        ];
+        for (error, expected) in cases {
+            assert_eq!(DbFailure::of_database(&error), expected, "{}", error.message);
+        }
+    }
+
+    #[test]
+    fn classifies_postgres_errors() {
+        let cases = [
+            (
+                postgres(
+                    "23505",
+                    ErrorKind::UniqueViolation,
+                    r#"duplicate key value violates unique constraint "user_account_username_key""#,
+                    "user_account",
+                    Some("user_account_username_key"),
+                ),
+                DbFailure::UniqueViolation { field: field("username") },
+            ),
+            (
+                postgres(
+                    "23505",
+                    ErrorKind::UniqueViolation,
+                    r#"duplicate key value violates unique constraint "user_account_pkey""#,
+                    "user_account",
+                    Some("user_account_pkey"),
+                ),
+                DbFailure::UniqueViolation { field: None },
+            ),
+            (
+                postgres(
+                    "23503",
+                    ErrorKind::ForeignKeyViolation,
+                    r#"insert or update on table "project" violates foreign key constraint "project_user_id_fkey""#,
+                    "project",
+                    Some("project_user_id_fkey"),
+                ),
+                DbFailure::ForeignKeyViolation { field: field("user_id"), still_referenced: Some(false) },
+            ),
+            (
+                postgres(
+                    "23503",
+                    ErrorKind::ForeignKeyViolation,
+                    r#"update or delete on table "user_account" violates foreign key constraint "project_user_id_fkey" on table "project""#,
+                    "project",
+                    Some("project_user_id_fkey"),
+                ),
+                DbFailure::ForeignKeyViolation { field: field("user_id"), still_referenced: Some(true) },
+            ),
+            (
+                postgres(
+                    "23502",
+                    ErrorKind::NotNullViolation,
+                    r#"null value in column "password" of relation "user_account" violates not-null constraint"#,
+                    "user_account",
+                    None,
+                ),
+                DbFailure::NotNullViolation { field: field("password") },
+            ),
+            (
+                postgres(
+                    "23514",
+                    ErrorKind::CheckViolation,
+                    r#"new row for relation "user_account" violates check constraint "user_account_username_check""#,
+                    "user_account",
+                    Some("user_account_username_check"),
+                ),
+                DbFailure::CheckViolation { field: field("username") },
+            ),
+        ];
+        for (error, expected) in cases {
+            assert_eq!(DbFailure::of_database(&error), expected, "{}", error.message);
+        }
+    }
+
+    #[test]
+    fn postgres_unavailable_codes() {
+        let unavailable = ["08000", "08006", "08P01", "53300", "57014", "57P01", "57P02", "57P03", "40001", "40P01"];
+        for code in unavailable {
+            let error = postgres(code, ErrorKind::Other, "server unavailable", "user_account", None);
+            assert_eq!(DbFailure::of_database(&error), DbFailure::Unavailable, "{code}");
+        }
+        for code in ["42P01", "42703", "22P02", "53100"] {
+            let error = postgres(code, ErrorKind::Other, "statement failed", "user_account", None);
+            assert_eq!(DbFailure::of_database(&error), DbFailure::Other, "{code}");
+        }
+    }
+
+    #[test]
+    fn still_referenced_reads_the_postgres_message() {
+        assert_eq!(DbFailure::still_referenced("update or delete on table \"user_account\""), Some(true));
+        assert_eq!(DbFailure::still_referenced("insert or update on table \"project\""), Some(false));
+        assert_eq!(DbFailure::still_referenced("FOREIGN KEY constraint failed"), None);
+    }
+
+    #[test]
+    fn raw_field_of_keeps_what_field_of_drops() {
+        let error = sqlite("275", ErrorKind::CheckViolation, "CHECK constraint failed: length(username) > 0");
+        assert_eq!(DbFailure::raw_field_of(&error).as_deref(), Some("length(username) > 0"));
+        assert_eq!(DbFailure::field_of(&error), None);
+
+        let unnamed = postgres("23505", ErrorKind::UniqueViolation, "duplicate key", "user_account", Some("user_account_pkey"));
+        assert_eq!(DbFailure::raw_field_of(&unnamed).as_deref(), Some(""));
+        assert_eq!(DbFailure::field_of(&unnamed), None);
+
+        let custom = postgres("23505", ErrorKind::UniqueViolation, "duplicate key", "data_export", Some("idx_data_export_user_in_progress"));
+        assert_eq!(DbFailure::field_of(&custom).as_deref(), Some("idx_data_export_user_in_progress"));
+    }
+}
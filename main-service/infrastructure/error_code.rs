@@ -0,0 +1,149 @@
+use serde::Serialize;
+
+/// Stable, machine readable identifier of an error. Clients match on `code`, never on the
+/// message, so a published code must not be renamed or reused for another meaning.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct ErrorCode {
+    pub code: &'static str,
+    /// Status usually sent with the code. Rejections may use a more specific one.
+    pub status: u16,
+    pub description: &'static str,
+}
+
+impl ErrorCode {
+    const fn new(code: &'static str, status: u16, description: &'static str) -> Self {
+        ErrorCode { code, status, description }
+    }
+
+    // One per `AppError` variant
+    pub const NOT_FOUND: Self = Self::new("NOT_FOUND", 404, "The requested record does not exist.");
+    pub const BAD_REQUEST: Self = Self::new("BAD_REQUEST", 400, "The request cannot be processed as sent.");
+    pub const AUTH_UNAUTHORIZED: Self = Self::new("AUTH_UNAUTHORIZED", 401, "The access token is missing or invalid.");
+    pub const AUTH_TOKEN_EXPIRED: Self = Self::new("AUTH_TOKEN_EXPIRED", 401, "The access token has expired, sign in or refresh it.");
+    pub const AUTH_FORBIDDEN: Self = Self::new("AUTH_FORBIDDEN", 403, "The current user lacks a permission required by the request.");
+    pub const INTERNAL_ERROR: Self = Self::new("INTERNAL_ERROR", 500, "An unexpected error occurred on the server.");
+    pub const INTERNAL_ERROR_WITH_CONTEXT: Self = Self::new("INTERNAL_ERROR_WITH_CONTEXT", 500, "An unexpected error occurred, the message describes the failed step.");
+    pub const CONFLICT: Self = Self::new("CONFLICT", 409, "The request conflicts with the current state of the record.");
+    pub const PRECONDITION_FAILED: Self = Self::new("PRECONDITION_FAILED", 412, "A precondition of the request does not hold.");
+    pub const REQUEST_INVALID_JSON: Self = Self::new("REQUEST_INVALID_JSON", 400, "The body is not valid JSON for this endpoint.");
+    pub const REQUEST_INVALID_QUERY: Self = Self::new("REQUEST_INVALID_QUERY", 400, "The query string cannot be parsed.");
+    pub const REQUEST_INVALID_PATH: Self = Self::new("REQUEST_INVALID_PATH", 400, "A path parameter cannot be parsed.");
+    pub const REQUEST_INVALID_MULTIPART: Self = Self::new("REQUEST_INVALID_MULTIPART", 400, "The multipart body is malformed or too large.");
+    pub const REQUEST_INVALID_BODY: Self = Self::new("REQUEST_INVALID_BODY", 400, "The MessagePack or CBOR body cannot be decoded.");
+    pub const REQUEST_NOT_MULTIPART: Self = Self::new("REQUEST_NOT_MULTIPART", 400, "The body is not multipart/form-data or lacks a boundary.");
+    pub const REQUEST_INVALID_FORM: Self = Self::new("REQUEST_INVALID_FORM", 400, "The body is not a valid url-encoded form for this endpoint.");
+    pub const REQUEST_BODY_TOO_LARGE: Self = Self::new("REQUEST_BODY_TOO_LARGE", 413, "The MessagePack or CBOR body exceeds the size accepted by the server.");
+    pub const REQUEST_JSON_TOO_LARGE: Self = Self::new("REQUEST_JSON_TOO_LARGE", 413, "The JSON body exceeds the size accepted by the server.");
+    pub const VALIDATION_FAILED: Self = Self::new("VALIDATION_FAILED", 400, "One or more fields failed validation, see `errors`.");
+    pub const VALIDATION_RULE_FAILED: Self = Self::new("VALIDATION_RULE_FAILED", 400, "A validation rule spanning several fields failed.");
+    pub const VALIDATION_FIELD_INVALID: Self = Self::new("VALIDATION_FIELD_INVALID", 400, "A single field is invalid, see `errors`.");
+    pub const VALIDATION_FIELDS_INVALID: Self = Self::new("VALIDATION_FIELDS_INVALID", 400, "Several fields are invalid, see `errors`.");
+    pub const UNPROCESSABLE_ENTITY: Self = Self::new("UNPROCESSABLE_ENTITY", 422, "The request is well formed but cannot be processed.");
+    pub const SERIALIZATION_ERROR: Self = Self::new("SERIALIZATION_ERROR", 500, "Data could not be converted to or from JSON.");
+    pub const UNEXPECTED_ERROR: Self = Self::new("UNEXPECTED_ERROR", 500, "An unexpected error occurred in a background step.");
+    pub const IO_ERROR: Self = Self::new("IO_ERROR", 500, "Reading or writing a file failed.");
+    pub const DATABASE_ERROR: Self = Self::new("DATABASE_ERROR", 500, "The database rejected the operation.");
+    pub const DATABASE_TRANSACTION_ERROR: Self = Self::new("DATABASE_TRANSACTION_ERROR", 500, "A database transaction failed.");
+    pub const DATABASE_DRIVER_ERROR: Self = Self::new("DATABASE_DRIVER_ERROR", 500, "The database driver reported an error.");
+    pub const DECODE_ERROR: Self = Self::new("DECODE_ERROR", 500, "A stored base64 value cannot be decoded.");
+    pub const PROJECT_PARTICIPANT_QUOTA_EXCEEDED: Self = Self::new("PROJECT_PARTICIPANT_QUOTA_EXCEEDED", 400, "The project has reached its participant quota.");
+    pub const PROJECT_PARTICIPANT_ALREADY_EXISTS: Self = Self::new("PROJECT_PARTICIPANT_ALREADY_EXISTS", 400, "The user already participates in the project.");
+    pub const PROJECT_VERSION_ID_MISMATCH: Self = Self::new("PROJECT_VERSION_ID_MISMATCH", 400, "The project version does not match the latest one.");
+    pub const VARIABLE_PARSE_FAILED: Self = Self::new("VARIABLE_PARSE_FAILED", 400, "A project variable cannot be parsed.");
+    pub const UUID_INVALID: Self = Self::new("UUID_INVALID", 500, "A stored value is not a valid UUID.");
+    pub const PASSWORD_HASH_ERROR: Self = Self::new("PASSWORD_HASH_ERROR", 500, "A password could not be hashed or verified.");
+
+    // Classified database failures, sent instead of the generic database codes
+    pub const DB_UNIQUE_VIOLATION: Self = Self::new("DB_UNIQUE_VIOLATION", 409, "A record with the same unique value already exists.");
+    pub const DB_FOREIGN_KEY_VIOLATION: Self = Self::new("DB_FOREIGN_KEY_VIOLATION", 409, "The change breaks a reference between records.");
+    pub const DB_NOT_NULL_VIOLATION: Self = Self::new("DB_NOT_NULL_VIOLATION", 400, "A required value is missing.");
+    pub const DB_CHECK_VIOLATION: Self = Self::new("DB_CHECK_VIOLATION", 400, "A value is outside of its allowed range.");
+    pub const DB_RECORD_NOT_FOUND: Self = Self::new("DB_RECORD_NOT_FOUND", 404, "The record to change does not exist.");
+    pub const DB_UNAVAILABLE: Self = Self::new("DB_UNAVAILABLE", 503, "The database is temporarily unavailable, retry after `Retry-After`.");
+
+    // Errors produced outside of handlers
+    pub const ROUTE_NOT_FOUND: Self = Self::new("ROUTE_NOT_FOUND", 404, "No endpoint matches the request path.");
+    pub const REQUEST_TIMEOUT: Self = Self::new("REQUEST_TIMEOUT", 408, "The request took longer than the server timeout.");
+    pub const SERVICE_ERROR: Self = Self::new("SERVICE_ERROR", 500, "A server middleware failed to process the request.");
+    pub const HTTP_ERROR: Self = Self::new("HTTP_ERROR", 400, "Any other HTTP error, e.g. a method that is not allowed.");
+
+    /// Every code the API can send.
+    pub const ALL: &'static [ErrorCode] = &[
+        Self::NOT_FOUND,
+        Self::BAD_REQUEST,
+        Self::AUTH_UNAUTHORIZED,
+        Self::AUTH_TOKEN_EXPIRED,
+        Self::AUTH_FORBIDDEN,
+        Self::INTERNAL_ERROR,
+        Self::INTERNAL_ERROR_WITH_CONTEXT,
+        Self::CONFLICT,
+        Self::PRECONDITION_FAILED,
+        Self::REQUEST_INVALID_JSON,
+        Self::REQUEST_INVALID_QUERY,
+        Self::REQUEST_INVALID_PATH,
+        Self::REQUEST_INVALID_MULTIPART,
+        Self::REQUEST_INVALID_BODY,
+        Self::REQUEST_NOT_MULTIPART,
+        Self::REQUEST_INVALID_FORM,
+        Self::REQUEST_BODY_TOO_LARGE,
+        Self::REQUEST_JSON_TOO_LARGE,
+        Self::VALIDATION_FAILED,
+        Self::VALIDATION_RULE_FAILED,
+        Self::VALIDATION_FIELD_INVALID,
+        Self::VALIDATION_FIELDS_INVALID,
+        Self::UNPROCESSABLE_ENTITY,
+        Self::SERIALIZATION_ERROR,
+        Self::UNEXPECTED_ERROR,
+        Self::IO_ERROR,
+        Self::DATABASE_ERROR,
+        Self::DATABASE_TRANSACTION_ERROR,
+        Self::DATABASE_DRIVER_ERROR,
+        Self::DECODE_ERROR,
+        Self::PROJECT_PARTICIPANT_QUOTA_EXCEEDED,
+        Self::PROJECT_PARTICIPANT_ALREADY_EXISTS,
+        Self::PROJECT_VERSION_ID_MISMATCH,
+        Self::VARIABLE_PARSE_FAILED,
+        Self::UUID_INVALID,
+        Self::PASSWORD_HASH_ERROR,
+        Self::DB_UNIQUE_VIOLATION,
+        Self::DB_FOREIGN_KEY_VIOLATION,
+        Self::DB_NOT_NULL_VIOLATION,
+        Self::DB_CHECK_VIOLATION,
+        Self::DB_RECORD_NOT_FOUND,
+        Self::DB_UNAVAILABLE,
+        Self::ROUTE_NOT_FOUND,
+        Self::REQUEST_TIMEOUT,
+        Self::SERVICE_ERROR,
+        Self::HTTP_ERROR,
+    ];
+
+    pub fn find(code: &str) -> Option<ErrorCode> {
+        Self::ALL.iter().copied().find(|entry| entry.code == code)
+    }
+
+    /// The catalogue as a markdown table for client developers.
+    pub fn catalogue_markdown() -> String {
+        let mut markdown = String::from("| Code | Status | Description |\n| --- | --- | --- |\n");
+        for entry in Self::ALL {
+            markdown.push_str(&format!(
+                "| `{}` | {} | {} |\n",
+                entry.code, entry.status, entry.description
+            ));
+        }
+        markdown
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ErrorCode;
+    use std::collections::HashSet;
+
+    #[test]
+    fn codes_are_unique() {
+        let mut seen = HashSet::new();
+        for entry in ErrorCode::ALL {
+            assert!(seen.insert(entry.code), "error code {} is used twice", entry.code);
+        }
+    }
+}
\ No newline at end of file
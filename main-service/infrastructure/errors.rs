@@ -2,9 +2,12 @@
 use std::borrow::Cow;
 use std::collections::HashMap;
 use std::fmt::{Debug, Display, Formatter};
+use std::future::Future;
 
-use axum::extract::rejection::{JsonRejection, PathRejection, QueryRejection};
+use axum::extract::multipart::{MultipartError, MultipartRejection};
+use axum::extract::rejection::{FormRejection, JsonRejection, PathRejection, QueryRejection};
 use axum::response::Response;
+use axum::http::header::RETRY_AFTER;
 use axum::{http::StatusCode, response::IntoResponse, Json};
 use base64::DecodeError;
 use sea_orm::{DbErr, TransactionError};
@@ -15,9 +18,22 @@ use tracing::debug;
 use tracing::log::error;
 use validator::{ValidationError, ValidationErrors, ValidationErrorsKind};
 use crate::dto::base::BaseResponse;
+use crate::infrastructure::db_error::DbFailure;
+use crate::infrastructure::validation_code::ValidationCode;
+use crate::infrastructure::i18n::I18n;
+use crate::infrastructure::error_code::ErrorCode;
+use crate::infrastructure::problem::Problem;
+use crate::infrastructure::uuid::generate_uuid;
 
 pub type AppResult<T> = Result<T, AppError>;
 
+pub const CORRELATION_ID_HEADER: &str = "x-correlation-id";
+
+tokio::task_local! {
+    /// `Config::expose_internal_errors` of the app serving the current request.
+    static EXPOSE_INTERNAL_ERRORS: bool;
+}
+
 pub type ErrorMap = HashMap<Cow<'static, str>, Vec<Cow<'static, str>>>;
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -41,12 +57,83 @@ impl ValidationMessageErrors {
     pub fn from(errors: Vec<ValidationMessageError>) -> Self {
         Self { errors }
     }
+
+    /// Every failed rule of a `validator` result, nested structs and list items included.
+    #[must_use]
+    pub fn of_validation(errors: &ValidationErrors) -> Self {
+        let mut validation_errors = Vec::new();
+        AppError::collect_validation_errors(errors, "", &mut validation_errors);
+        // The errors come out of hash maps, keep the response stable
+        validation_errors.sort_by(|a, b| a.pointer.cmp(&b.pointer));
+        Self { errors: validation_errors }
+    }
 }
 
-#[derive(Debug, Error, Serialize)]
+#[derive(Clone, Debug, Error, Serialize)]
 pub struct ValidationMessageError {
     pub field: String,
+    /// JSON pointer of the field in the request, e.g. `/participants/2/email`.
+    pub pointer: String,
     pub message: String,
+    /// Validator code, also the key the message is looked up by in the message catalogs.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<Cow<'static, str>>,
+    /// Parameters of the rule, e.g. `min` and `max` of a length.
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub params: HashMap<Cow<'static, str>, serde_json::Value>,
+}
+
+impl ValidationMessageError {
+    #[must_use]
+    pub fn new(field: impl Into<String>, code: &'static str, message: impl Into<String>) -> Self {
+        let field = field.into();
+        Self {
+            pointer: format!("/{}", escape_pointer(&field)),
+            field,
+            message: message.into(),
+            code: Some(Cow::from(code)),
+            params: HashMap::new(),
+        }
+    }
+
+    /// A failed `validator` rule of `field`, located at `pointer`.
+    #[must_use]
+    pub fn of_rule(field: &str, pointer: String, error: &ValidationError) -> Self {
+        // `validator` adds the submitted value, drop it so passwords and tokens are not echoed
+        // back in responses or written to logs
+        let params: HashMap<Cow<'static, str>, serde_json::Value> = error
+            .params
+            .iter()
+            .filter(|(key, _value)| *key != "value")
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        let message = I18n::field_message(field, &error.code, &params, error.message.as_deref());
+        let message = message.unwrap_or_else(|| {
+            let params: Vec<String> = params
+                .iter()
+                .map(|(key, value)| format!("{key} value is {value}"))
+                .collect();
+
+            if params.is_empty() {
+                format!("{field} is required")
+            } else {
+                params.join(", ")
+            }
+        });
+        Self {
+            field: field.to_string(),
+            pointer,
+            message,
+            code: Some(error.code.clone()),
+            params,
+        }
+    }
+
+    #[must_use]
+    pub fn with_param(mut self, key: &'static str, value: impl Into<serde_json::Value>) -> Self {
+        self.params.insert(Cow::from(key), value.into());
+        self
+    }
 }
 
 impl Display for ValidationMessageError {
@@ -55,6 +142,15 @@ impl Display for ValidationMessageError {
     }
 }
 
+/// Escape a field name as a JSON pointer reference token, see RFC 6901.
+fn escape_pointer(field: &str) -> Cow<'_, str> {
+    if field.contains(['~', '/']) {
+        Cow::from(field.replace('~', "~0").replace('/', "~1"))
+    } else {
+        Cow::from(field)
+    }
+}
+
 impl Display for ValidationMessageErrors {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "Field error: {}", self.errors.len())
@@ -69,6 +165,8 @@ pub enum AppError {
     BadRequest(String),
     #[error("authentication is required to access this resource")]
     Unauthorized,
+    #[error("the access token has expired")]
+    TokenExpired,
     #[error("user does not have privilege to access this resource")]
     Forbidden(String),
     #[error("unexpected error has occurred")]
@@ -86,6 +184,16 @@ pub enum AppError {
     #[error(transparent)]
     AxumPathRejection(#[from] PathRejection),
     #[error(transparent)]
+    AxumMultipartError(#[from] MultipartError),
+    #[error(transparent)]
+    AxumMultipartRejection(#[from] MultipartRejection),
+    #[error(transparent)]
+    AxumFormRejection(#[from] FormRejection),
+    #[error("{0}")]
+    UndecodableBody(String),
+    #[error("{0}")]
+    PayloadTooLarge(String),
+    #[error(transparent)]
     ValidationErrors(#[from] ValidationErrors),
     #[error(transparent)]
     ValidationError(#[from] ValidationError),
@@ -122,63 +230,297 @@ pub enum AppError {
     FailedParsingVariable,
     #[error(transparent)]
     UuidParseError(#[from] uuid::Error),
+    #[error("{0}")]
+    PasswordHashError(#[from] argon2::password_hash::Error),
 }
 
 impl AppError {
     #[must_use]
-    /// Maps `validator`'s `ValidationrErrors` to a simple map of property name/error messages structure.
+    /// Maps `validator`'s `ValidationErrors` to a list of failed rules, nested structs and list
+    /// items included, each located by the JSON pointer of its field.
     pub fn unprocessable_entity(errors: ValidationErrors) -> Response {
-        let mut validation_errors = ErrorMap::new();
-
-        for (field_property, error_kind) in errors.into_errors() {
-            if let ValidationErrorsKind::Field(field_meta) = error_kind.clone() {
-                for error in field_meta {
-                    validation_errors
-                        .entry(Cow::from(field_property))
-                        .or_default()
-                        .push(error.message.unwrap_or_else(|| {
-                            let params: Vec<Cow<'static, str>> = error
-                                .params
-                                .iter()
-                                .filter(|(key, _value)| *key != "value")
-                                .map(|(key, value)| Cow::from(format!("{key} value is {value}")))
-                                .collect();
-
-                            if params.is_empty() {
-                                Cow::from(format!("{field_property} is required"))
-                            } else {
-                                Cow::from(params.join(", "))
-                            }
-                        }));
+        let validation_errors = ValidationMessageErrors::of_validation(&errors).errors;
+        Self::field_errors_response(StatusCode::BAD_REQUEST, ErrorCode::VALIDATION_FAILED, validation_errors)
+    }
+
+    fn collect_validation_errors(
+        errors: &ValidationErrors,
+        parent: &str,
+        validation_errors: &mut Vec<ValidationMessageError>,
+    ) {
+        for (field_property, error_kind) in errors.errors() {
+            // Struct level rules are reported under the struct itself
+            let pointer = match *field_property {
+                "__all__" => parent.to_string(),
+                field => format!("{parent}/{}", escape_pointer(field)),
+            };
+            match error_kind {
+                ValidationErrorsKind::Field(field_meta) => {
+                    for error in field_meta {
+                        validation_errors.push(ValidationMessageError::of_rule(
+                            field_property,
+                            pointer.clone(),
+                            error,
+                        ));
+                    }
+                }
+                ValidationErrorsKind::Struct(errors) => {
+                    Self::collect_validation_errors(errors, &pointer, validation_errors);
+                }
+                ValidationErrorsKind::List(items) => {
+                    for (index, errors) in items {
+                        let pointer = format!("{pointer}/{index}");
+                        Self::collect_validation_errors(errors, &pointer, validation_errors);
+                    }
                 }
             }
         }
+    }
 
+    fn error_map_response(status: StatusCode, code: ErrorCode, errors: ErrorMap) -> Response {
+        let problem = Problem::validation(
+            status,
+            code,
+            errors
+                .iter()
+                .flat_map(|(field, messages)| {
+                    messages.iter().map(|message| ValidationMessageError {
+                        field: field.to_string(),
+                        pointer: format!("/{}", escape_pointer(field)),
+                        message: message.to_string(),
+                        code: None,
+                        params: HashMap::new(),
+                    })
+                })
+                .collect(),
+        );
         let body = Json(json!({
-            "errors": validation_errors,
+            "code": code.code,
+            "errors": errors,
         }));
 
-        (StatusCode::BAD_REQUEST, body).into_response()
+        problem.attach((status, body).into_response())
     }
 
     #[must_use]
     /// parse validation to list of field and error
     pub fn parse_validation_errors(errors: ValidationMessageErrors) -> Response {
-        let body = Json(json!({
-            "errors": errors.errors,
-        }));
-
-        (StatusCode::BAD_REQUEST, body).into_response()
+        Self::field_errors_response(
+            StatusCode::BAD_REQUEST,
+            ErrorCode::VALIDATION_FIELDS_INVALID,
+            errors.errors,
+        )
     }
 
     #[must_use]
     /// parse validation to list of field and error
     pub fn parse_validation_error(error: ValidationMessageError) -> Response {
+        Self::field_errors_response(
+            StatusCode::BAD_REQUEST,
+            ErrorCode::VALIDATION_FIELD_INVALID,
+            vec![error],
+        )
+    }
+
+    fn field_errors_response(
+        status: StatusCode,
+        code: ErrorCode,
+        errors: Vec<ValidationMessageError>,
+    ) -> Response {
+        let errors: Vec<ValidationMessageError> = errors
+            .into_iter()
+            .map(|mut error| {
+                if let Some(code) = &error.code {
+                    let message =
+                        I18n::field_message(&error.field, code, &error.params, Some(&error.message));
+                    error.message = message.unwrap_or(error.message);
+                }
+                error
+            })
+            .collect();
+        let problem = Problem::validation(status, code, errors.clone());
         let body = Json(json!({
-            "errors": vec![error],
+            "code": code.code,
+            "errors": errors,
         }));
 
-        (StatusCode::BAD_REQUEST, body).into_response()
+        problem.attach((status, body).into_response())
+    }
+}
+
+impl AppError {
+    /// Run `future` with server errors showing their cause to clients or not, see
+    /// `Config::expose_internal_errors`. Outside of a scope causes are never shown.
+    pub async fn scope_internal_errors<F: Future>(expose: bool, future: F) -> F::Output {
+        EXPOSE_INTERNAL_ERRORS.scope(expose, future).await
+    }
+
+    pub fn exposes_internal_errors() -> bool {
+        EXPOSE_INTERNAL_ERRORS.try_with(|expose| *expose).unwrap_or(false)
+    }
+
+    /// Log the full error chain at error level under a new correlation id and return the id.
+    pub fn correlate(source: &dyn std::error::Error) -> String {
+        let correlation_id = generate_uuid().to_string();
+        let mut chain = source.to_string();
+        let mut cause = source.source();
+        while let Some(err) = cause {
+            let message = err.to_string();
+            // `#[error("{0}")]` variants repeat their source
+            if !chain.ends_with(&message) {
+                chain.push_str(": ");
+                chain.push_str(&message);
+            }
+            cause = err.source();
+        }
+        tracing::error!(correlation_id = %correlation_id, "internal error: {chain}");
+        correlation_id
+    }
+
+    /// The detail of an internal error as clients may see it.
+    pub fn public_message(detail: String) -> String {
+        if Self::exposes_internal_errors() {
+            detail
+        } else {
+            Self::InternalServerError.to_string()
+        }
+    }
+
+    #[must_use]
+    /// Respond to a server side failure. Clients get a correlation id to quote to support
+    /// while the cause itself stays in the logs outside of development.
+    pub fn internal_error(
+        status: StatusCode,
+        code: ErrorCode,
+        detail: String,
+        source: &dyn std::error::Error,
+    ) -> Response {
+        let correlation_id = Self::correlate(source);
+        let message = I18n::error_message(code, Self::public_message(detail));
+        let problem =
+            Problem::new(status, code, message.clone()).with_correlation_id(correlation_id.clone());
+        let body = Json(
+            BaseResponse::<()>::error(message, code.code, Some(status.as_u16()))
+                .with_correlation_id(correlation_id.clone()),
+        );
+        problem.attach((status, [(CORRELATION_ID_HEADER, correlation_id)], body).into_response())
+    }
+
+    /// Stable code of the variant, listed in `ErrorCode::ALL`. Each variant has its own.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::NotFound(_) => ErrorCode::NOT_FOUND,
+            Self::BadRequest(_) => ErrorCode::BAD_REQUEST,
+            Self::Unauthorized => ErrorCode::AUTH_UNAUTHORIZED,
+            Self::TokenExpired => ErrorCode::AUTH_TOKEN_EXPIRED,
+            Self::Forbidden(_) => ErrorCode::AUTH_FORBIDDEN,
+            Self::InternalServerError => ErrorCode::INTERNAL_ERROR,
+            Self::InternalServerErrorWithContext(_) => ErrorCode::INTERNAL_ERROR_WITH_CONTEXT,
+            Self::Conflict(_) => ErrorCode::CONFLICT,
+            Self::PreconditionFailed(_) => ErrorCode::PRECONDITION_FAILED,
+            Self::AxumJsonRejection(err) if err.status() == StatusCode::PAYLOAD_TOO_LARGE => {
+                ErrorCode::REQUEST_JSON_TOO_LARGE
+            }
+            Self::AxumJsonRejection(_) => ErrorCode::REQUEST_INVALID_JSON,
+            Self::AxumQueryRejection(_) => ErrorCode::REQUEST_INVALID_QUERY,
+            Self::AxumPathRejection(_) => ErrorCode::REQUEST_INVALID_PATH,
+            Self::AxumMultipartError(_) => ErrorCode::REQUEST_INVALID_MULTIPART,
+            Self::AxumMultipartRejection(_) => ErrorCode::REQUEST_NOT_MULTIPART,
+            Self::AxumFormRejection(_) => ErrorCode::REQUEST_INVALID_FORM,
+            Self::UndecodableBody(_) => ErrorCode::REQUEST_INVALID_BODY,
+            Self::PayloadTooLarge(_) => ErrorCode::REQUEST_BODY_TOO_LARGE,
+            Self::ValidationErrors(_) => ErrorCode::VALIDATION_FAILED,
+            Self::ValidationError(_) => ErrorCode::VALIDATION_RULE_FAILED,
+            Self::UnprocessableEntity { .. } => ErrorCode::UNPROCESSABLE_ENTITY,
+            Self::SerdeJsonError(_) => ErrorCode::SERIALIZATION_ERROR,
+            Self::AnyhowError(_) => ErrorCode::UNEXPECTED_ERROR,
+            Self::ConfigError(_) => ErrorCode::IO_ERROR,
+            Self::DbError(_) => ErrorCode::DATABASE_ERROR,
+            Self::TransactionError(_) => ErrorCode::DATABASE_TRANSACTION_ERROR,
+            Self::DecodeError(_) => ErrorCode::DECODE_ERROR,
+            Self::ParticipantQuotaExceeded => ErrorCode::PROJECT_PARTICIPANT_QUOTA_EXCEEDED,
+            Self::ParticipantAlreadyExists => ErrorCode::PROJECT_PARTICIPANT_ALREADY_EXISTS,
+            Self::ValidationMessageError(_) => ErrorCode::VALIDATION_FIELD_INVALID,
+            Self::ValidationMessageErrors(_) => ErrorCode::VALIDATION_FIELDS_INVALID,
+            Self::SqlxDbError(_) => ErrorCode::DATABASE_DRIVER_ERROR,
+            Self::ProjectVersionIdMismatch => ErrorCode::PROJECT_VERSION_ID_MISMATCH,
+            Self::FailedParsingVariable => ErrorCode::VARIABLE_PARSE_FAILED,
+            Self::UuidParseError(_) => ErrorCode::UUID_INVALID,
+            Self::PasswordHashError(_) => ErrorCode::PASSWORD_HASH_ERROR,
+        }
+    }
+
+    #[must_use]
+    /// Map a classified database failure to a client facing response. Raw driver messages
+    /// never reach the client, unexpected failures are logged instead.
+    pub fn database_error(failure: DbFailure, source: &AppError) -> Response {
+        let code = failure.code().unwrap_or_else(|| source.code());
+        let field_error = |status: StatusCode, field: String, rule: &'static str, message: String| {
+            let errors = vec![ValidationMessageError::new(field, rule, message)];
+            Self::field_errors_response(status, code, errors)
+        };
+
+        let (status, message) = match failure {
+            DbFailure::UniqueViolation { field: Some(field) } => {
+                let message = format!("{field} already exists");
+                return field_error(StatusCode::CONFLICT, field, ValidationCode::UNIQUE, message);
+            }
+            DbFailure::UniqueViolation { field: None } => {
+                (StatusCode::CONFLICT, "record already exists".to_string())
+            }
+            DbFailure::ForeignKeyViolation { still_referenced: Some(true), .. } => (
+                StatusCode::CONFLICT,
+                "record is still referenced by other records".to_string(),
+            ),
+            DbFailure::ForeignKeyViolation { field: Some(field), still_referenced: Some(false) } => {
+                let message = format!("{field} references a record that does not exist");
+                let code = ValidationCode::FOREIGN_KEY;
+                return field_error(StatusCode::BAD_REQUEST, field, code, message);
+            }
+            DbFailure::ForeignKeyViolation { still_referenced: Some(false), .. } => (
+                StatusCode::BAD_REQUEST,
+                "referenced record does not exist".to_string(),
+            ),
+            DbFailure::ForeignKeyViolation { still_referenced: None, .. } => (
+                StatusCode::CONFLICT,
+                "change conflicts with related records".to_string(),
+            ),
+            DbFailure::NotNullViolation { field: Some(field) } => {
+                let message = format!("{field} is required");
+                return field_error(StatusCode::BAD_REQUEST, field, ValidationCode::REQUIRED, message);
+            }
+            DbFailure::NotNullViolation { field: None } => {
+                (StatusCode::BAD_REQUEST, "a required value is missing".to_string())
+            }
+            DbFailure::CheckViolation { field: Some(field) } => {
+                let message = format!("{field} is invalid");
+                return field_error(StatusCode::BAD_REQUEST, field, ValidationCode::INVALID, message);
+            }
+            DbFailure::CheckViolation { field: None } => {
+                (StatusCode::BAD_REQUEST, "a value is invalid".to_string())
+            }
+            DbFailure::NotFound => (StatusCode::NOT_FOUND, "record not found".to_string()),
+            DbFailure::Unavailable => {
+                tracing::warn!("database unavailable: {source}");
+                let status = StatusCode::SERVICE_UNAVAILABLE;
+                let message = I18n::error_message(
+                    code,
+                    "service is temporarily unavailable, please retry".to_string(),
+                );
+                let problem = Problem::new(status, code, message.clone());
+                let body = Json(BaseResponse::<()>::error(message, code.code, Some(status.as_u16())));
+                return problem.attach((status, [(RETRY_AFTER, "1")], body).into_response());
+            }
+            DbFailure::Other => {
+                let status = StatusCode::INTERNAL_SERVER_ERROR;
+                return Self::internal_error(status, code, source.to_string(), source);
+            }
+        };
+
+        let message = I18n::error_message(code, message);
+        let problem = Problem::new(status, code, message.clone());
+        let body = Json(BaseResponse::<()>::error(message, code.code, Some(status.as_u16())));
+        problem.attach((status, body).into_response())
     }
 }
 
@@ -197,30 +539,243 @@ impl IntoResponse for AppError {
             return Self::parse_validation_error(e);
         }
 
+        // A rule without a field, reported like the struct level rules of `ValidationErrors`
+        // so its code is looked up in the message catalogs too
+        if let Self::ValidationError(e) = self {
+            let error = ValidationMessageError::of_rule("__all__", String::new(), &e);
+            return Self::field_errors_response(
+                StatusCode::BAD_REQUEST,
+                ErrorCode::VALIDATION_RULE_FAILED,
+                vec![error],
+            );
+        }
 
-        let (status, error_message) = match self {
-            Self::InternalServerErrorWithContext(err) => (StatusCode::INTERNAL_SERVER_ERROR, err),
-            Self::NotFound(err) => (StatusCode::NOT_FOUND, err),
-            Self::Conflict(err) => (StatusCode::CONFLICT, err),
-            Self::PreconditionFailed(err) => (StatusCode::PRECONDITION_FAILED, err),
-            Self::BadRequest(err) => (StatusCode::BAD_REQUEST, err),
+        let failure = match &self {
+            Self::DbError(err)
+            | Self::TransactionError(TransactionError::Connection(err))
+            | Self::TransactionError(TransactionError::Transaction(err)) => Some(DbFailure::of(err)),
+            Self::SqlxDbError(err) => Some(DbFailure::of_sqlx(err)),
+            _ => None,
+        };
+        if let Some(failure) = failure {
+            return Self::database_error(failure, &self);
+        }
+
+        let code = self.code();
+        if let Self::UnprocessableEntity { errors } = self {
+            return Self::error_map_response(StatusCode::UNPROCESSABLE_ENTITY, code, errors);
+        }
+
+        let (status, error_message) = match &self {
+            Self::InternalServerErrorWithContext(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.clone()),
+            Self::NotFound(err) => (StatusCode::NOT_FOUND, err.clone()),
+            Self::Conflict(err) => (StatusCode::CONFLICT, err.clone()),
+            Self::PreconditionFailed(err) => (StatusCode::PRECONDITION_FAILED, err.clone()),
+            Self::BadRequest(err) => (StatusCode::BAD_REQUEST, err.clone()),
             Self::Unauthorized => (StatusCode::UNAUTHORIZED, Self::Unauthorized.to_string()),
-            Self::Forbidden(err) => (StatusCode::FORBIDDEN, err),
+            Self::TokenExpired => (StatusCode::UNAUTHORIZED, Self::TokenExpired.to_string()),
+            Self::Forbidden(err) => (StatusCode::FORBIDDEN, err.clone()),
+            Self::AxumJsonRejection(err) if err.status() == StatusCode::PAYLOAD_TOO_LARGE => {
+                (StatusCode::PAYLOAD_TOO_LARGE, err.body_text())
+            }
             Self::AxumJsonRejection(err) => (StatusCode::BAD_REQUEST, err.body_text()),
+            Self::AxumQueryRejection(err) => (err.status(), err.body_text()),
+            Self::AxumPathRejection(err) => (err.status(), err.body_text()),
+            Self::AxumMultipartError(err) => (err.status(), err.body_text()),
+            Self::AxumMultipartRejection(err) => (err.status(), err.body_text()),
+            Self::AxumFormRejection(err) => (err.status(), err.body_text()),
+            Self::UndecodableBody(err) => (StatusCode::BAD_REQUEST, err.clone()),
+            Self::PayloadTooLarge(err) => (StatusCode::PAYLOAD_TOO_LARGE, err.clone()),
             Self::ParticipantAlreadyExists => (StatusCode::BAD_REQUEST, Self::ParticipantAlreadyExists.to_string()),
             Self::ParticipantQuotaExceeded => (StatusCode::BAD_REQUEST, Self::ParticipantQuotaExceeded.to_string()),
             Self::ProjectVersionIdMismatch => (StatusCode::BAD_REQUEST, Self::ProjectVersionIdMismatch.to_string()),
-            Self::DbError(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
             Self::FailedParsingVariable => (StatusCode::BAD_REQUEST, Self::FailedParsingVariable.to_string()),
-            Self::UuidParseError(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
-            _ => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Self::InternalServerError.to_string(),
-            ),
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
         };
+        if status.is_server_error() {
+            return Self::internal_error(status, code, error_message, &self);
+        }
 
-        let body = Json(BaseResponse::<()>::error(error_message, Some(status.as_u16())));
+        let error_message = I18n::error_message(code, error_message);
+        let problem = Problem::new(status, code, error_message.clone());
+        let body = Json(BaseResponse::<()>::error(error_message, code.code, Some(status.as_u16())));
 
-        (status, body).into_response()
+        problem.attach((status, body).into_response())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::AppError;
+    use crate::infrastructure::error_code::ErrorCode;
+    use axum::extract::rejection::{
+        FormRejection, InvalidFormContentType, JsonRejection, MissingJsonContentType,
+        MissingPathParams, PathRejection,
+    };
+    use axum::extract::multipart::{InvalidBoundary, MultipartRejection};
+    use axum::extract::{FromRequest, Multipart, Query, Request};
+    use axum::http::Uri;
+    use std::collections::HashMap;
+
+    async fn multipart_error() -> axum::extract::multipart::MultipartError {
+        let request = Request::builder()
+            .header("content-type", "multipart/form-data; boundary=x")
+            .body(axum::body::Body::from("--x\r\nbroken"))
+            .unwrap();
+        let mut multipart = Multipart::from_request(request, &()).await.unwrap();
+        multipart.next_field().await.unwrap_err()
+    }
+
+    async fn json_too_large() -> JsonRejection {
+        use tower::{Layer, ServiceExt};
+
+        let extract = tower::service_fn(|request: Request| async move {
+            axum::Json::<serde_json::Value>::from_request(request, &()).await.map(|_| ())
+        });
+        let request = Request::builder()
+            .header("content-type", "application/json")
+            .body(axum::body::Body::from("[1, 2, 3]"))
+            .unwrap();
+        let result = axum::extract::DefaultBodyLimit::max(1).layer(extract).oneshot(request).await;
+        result.expect_err("the body exceeds its limit")
+    }
+
+    /// One value of every variant, and of every `JsonRejection` status. Extend it together
+    /// with `AppError::code`.
+    async fn every_variant() -> Vec<AppError> {
+        let query = Query::<HashMap<String, u8>>::try_from_uri(&Uri::from_static("/?a=x"));
+        vec![
+            AppError::NotFound(String::new()),
+            AppError::BadRequest(String::new()),
+            AppError::Unauthorized,
+            AppError::TokenExpired,
+            AppError::Forbidden(String::new()),
+            AppError::InternalServerError,
+            AppError::InternalServerErrorWithContext(String::new()),
+            AppError::Conflict(String::new()),
+            AppError::PreconditionFailed(String::new()),
+            AppError::AxumJsonRejection(JsonRejection::MissingJsonContentType(
+                MissingJsonContentType::default(),
+            )),
+            AppError::AxumJsonRejection(json_too_large().await),
+            AppError::AxumQueryRejection(query.unwrap_err()),
+            AppError::AxumPathRejection(PathRejection::MissingPathParams(
+                MissingPathParams::default(),
+            )),
+            AppError::AxumMultipartError(multipart_error().await),
+            AppError::AxumMultipartRejection(MultipartRejection::InvalidBoundary(
+                InvalidBoundary::default(),
+            )),
+            AppError::AxumFormRejection(FormRejection::InvalidFormContentType(
+                InvalidFormContentType::default(),
+            )),
+            AppError::UndecodableBody(String::new()),
+            AppError::PayloadTooLarge(String::new()),
+            AppError::ValidationErrors(validator::ValidationErrors::new()),
+            AppError::ValidationError(validator::ValidationError::new("rule")),
+            AppError::UnprocessableEntity { errors: Default::default() },
+            AppError::SerdeJsonError(serde_json::from_str::<u8>("x").unwrap_err()),
+            AppError::AnyhowError(anyhow::anyhow!("error")),
+            AppError::ConfigError(std::io::Error::other("error")),
+            AppError::DbError(sea_orm::DbErr::Custom(String::new())),
+            AppError::TransactionError(sea_orm::TransactionError::Transaction(
+                sea_orm::DbErr::Custom(String::new()),
+            )),
+            AppError::DecodeError(base64::DecodeError::InvalidLength(1)),
+            AppError::ParticipantQuotaExceeded,
+            AppError::ParticipantAlreadyExists,
+            AppError::ValidationMessageError(super::ValidationMessageError::new("", "invalid", "")),
+            AppError::ValidationMessageErrors(super::ValidationMessageErrors::from(vec![])),
+            AppError::SqlxDbError(sea_orm::sqlx::Error::RowNotFound),
+            AppError::ProjectVersionIdMismatch,
+            AppError::FailedParsingVariable,
+            AppError::UuidParseError(uuid::Uuid::parse_str("x").unwrap_err()),
+            AppError::PasswordHashError(argon2::password_hash::Error::Password),
+        ]
+    }
+
+    fn pointers(errors: &validator::ValidationErrors) -> Vec<(String, String)> {
+        super::ValidationMessageErrors::of_validation(errors)
+            .errors
+            .into_iter()
+            .map(|error| (error.pointer, error.code.unwrap_or_default().to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn validation_errors_are_located_by_json_pointer() {
+        use validator::Validate;
+
+        fn no_self_invite(form: &Invite) -> Result<(), validator::ValidationError> {
+            match form.email == form.invited_by.email {
+                true => Err(validator::ValidationError::new("self_invite")),
+                false => Ok(()),
+            }
+        }
+
+        #[derive(Validate)]
+        struct Person {
+            #[validate(email)]
+            email: String,
+        }
+
+        #[derive(Validate)]
+        #[validate(schema(function = "no_self_invite", skip_on_field_errors = false))]
+        struct Invite {
+            #[validate(email)]
+            email: String,
+            #[validate(nested)]
+            invited_by: Person,
+            #[validate(nested)]
+            participants: Vec<Person>,
+        }
+
+        let person = |email: &str| Person { email: email.to_string() };
+        let invite = Invite {
+            email: "x".to_string(),
+            invited_by: person("x"),
+            participants: vec![person("a@b.c"), person("y"), person("z")],
+        };
+        assert_eq!(
+            pointers(&invite.validate().unwrap_err()),
+            [
+                ("", "self_invite"),
+                ("/email", "email"),
+                ("/invited_by/email", "email"),
+                ("/participants/1/email", "email"),
+                ("/participants/2/email", "email"),
+            ]
+            .map(|(pointer, code)| (pointer.to_string(), code.to_string()))
+        );
+    }
+
+    #[test]
+    fn pointer_segments_are_escaped() {
+        let mut errors = validator::ValidationErrors::new();
+        errors.add("a/b~c", validator::ValidationError::new("invalid"));
+        assert_eq!(pointers(&errors), [("/a~1b~0c".to_string(), "invalid".to_string())]);
+        assert_eq!(super::ValidationMessageError::new("~/", "invalid", "").pointer, "/~0~1");
+    }
+
+    #[test]
+    fn rule_errors_drop_the_submitted_value() {
+        let mut error = validator::ValidationError::new("length");
+        error.add_param("value".into(), &"hunter2");
+        error.add_param("min".into(), &8);
+        let error = super::ValidationMessageError::of_rule("password", "/password".to_string(), &error);
+        assert!(!error.params.contains_key("value"));
+        assert_eq!(error.params["min"], 8);
+    }
+
+    #[tokio::test]
+    async fn every_variant_has_its_own_registered_code() {
+        let mut variants: HashMap<&str, String> = HashMap::new();
+        for error in every_variant().await {
+            let code = error.code();
+            assert_eq!(ErrorCode::find(code.code), Some(code), "{} is not in ErrorCode::ALL", code.code);
+            if let Some(other) = variants.insert(code.code, format!("{error:?}")) {
+                panic!("{} is shared by {other} and {error:?}", code.code);
+            }
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,194 @@
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::future::Future;
+
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource, FluentValue};
+use fluent_langneg::{accepted_languages, negotiate_languages, NegotiationStrategy};
+use lazy_static::lazy_static;
+use unic_langid::LanguageIdentifier;
+use crate::infrastructure::error_code::ErrorCode;
+
+/// Language of the messages written in code. Catalogs of other locales translate them.
+pub const SOURCE_LOCALE: &str = "en";
+
+const CATALOGS: [(&str, &str); 3] = [
+    ("en", include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/locales/en.ftl"))),
+    ("de", include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/locales/de.ftl"))),
+    ("es", include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/locales/es.ftl"))),
+];
+
+lazy_static! {
+    static ref BUNDLES: Vec<(LanguageIdentifier, FluentBundle<FluentResource>)> = CATALOGS
+        .iter()
+        .map(|(locale, source)| {
+            let locale: LanguageIdentifier = locale.parse().expect("catalog locale is invalid");
+            let resource = FluentResource::try_new(source.to_string())
+                .unwrap_or_else(|(_, errors)| panic!("catalog {locale} is invalid: {errors:?}"));
+            let mut bundle = FluentBundle::new_concurrent(vec![locale.clone()]);
+            // Messages end up in JSON, not in bidirectional text
+            bundle.set_use_isolating(false);
+            bundle
+                .add_resource(resource)
+                .unwrap_or_else(|errors| panic!("catalog {locale} is invalid: {errors:?}"));
+            (locale, bundle)
+        })
+        .collect();
+}
+
+tokio::task_local! {
+    static REQUEST_LOCALE: RefCell<LanguageIdentifier>;
+}
+
+/// Message catalogs and the locale negotiated for the current request.
+pub struct I18n;
+
+impl I18n {
+    /// Parse the catalogs now instead of on the first translated message.
+    pub fn init() {
+        lazy_static::initialize(&BUNDLES);
+    }
+
+    pub fn source_locale() -> LanguageIdentifier {
+        LanguageIdentifier::from_bytes(SOURCE_LOCALE.as_bytes()).unwrap_or_default()
+    }
+
+    /// Best supported locale for an `Accept-Language` value or a single language tag.
+    pub fn negotiate(requested: &str) -> Option<LanguageIdentifier> {
+        let requested = accepted_languages::parse(requested);
+        let available: Vec<&LanguageIdentifier> = BUNDLES.iter().map(|(locale, _)| locale).collect();
+        negotiate_languages(&requested, &available, None, NegotiationStrategy::Lookup)
+            .first()
+            .map(|locale| (**locale).clone())
+    }
+
+    /// Run `future` with `locale` as the locale of every message it produces.
+    pub async fn scope<F: Future>(locale: LanguageIdentifier, future: F) -> F::Output {
+        REQUEST_LOCALE.scope(RefCell::new(locale), future).await
+    }
+
+    pub fn current() -> LanguageIdentifier {
+        REQUEST_LOCALE
+            .try_with(|locale| locale.borrow().clone())
+            .unwrap_or_else(|_| Self::source_locale())
+    }
+
+    /// Switch the locale of the running request, e.g. to the one in the user's profile.
+    pub fn set_current(locale: LanguageIdentifier) {
+        let _ = REQUEST_LOCALE.try_with(|current| *current.borrow_mut() = locale);
+    }
+
+    fn is_source() -> bool {
+        Self::current() == Self::source_locale()
+    }
+
+    /// Format the catalog message `id` in the current locale.
+    pub fn translate(id: &str, args: Option<&FluentArgs>) -> Option<String> {
+        let current = Self::current();
+        let (_, bundle) = BUNDLES.iter().find(|(locale, _)| *locale == current)?;
+        let pattern = bundle.get_message(id)?.value()?;
+        let mut errors = Vec::new();
+        let message = bundle.format_pattern(pattern, args, &mut errors);
+        if !errors.is_empty() {
+            tracing::warn!("message {id} in {current} could not be formatted: {errors:?}");
+        }
+        Some(message.into_owned())
+    }
+
+    /// Message of a failed field rule, looked up as `validation-{code}`. Rules bounded by
+    /// `min`/`max`/`equal` first try the `-min`, `-max`, `-between` or `-equal` variant.
+    /// Messages written in code win as long as the request uses the source locale.
+    pub fn field_message(
+        field: &str,
+        code: &str,
+        params: &HashMap<Cow<'static, str>, serde_json::Value>,
+        message: Option<&str>,
+    ) -> Option<String> {
+        if let Some(message) = message.filter(|_| Self::is_source()) {
+            return Some(message.to_string());
+        }
+
+        let mut args = FluentArgs::new();
+        args.set("field", field.to_string());
+        for (key, value) in params {
+            let value = match value {
+                serde_json::Value::Number(number) => FluentValue::from(number.as_f64().unwrap_or_default()),
+                serde_json::Value::String(text) => FluentValue::from(text.clone()),
+                other => FluentValue::from(other.to_string()),
+            };
+            args.set(key.to_string(), value);
+        }
+
+        let variant = match (params.contains_key("min"), params.contains_key("max")) {
+            _ if params.contains_key("equal") => Some("equal"),
+            (true, true) => Some("between"),
+            (true, false) => Some("min"),
+            (false, true) => Some("max"),
+            (false, false) => None,
+        };
+        variant
+            .and_then(|variant| Self::translate(&format!("validation-{code}-{variant}"), Some(&args)))
+            .or_else(|| Self::translate(&format!("validation-{code}"), Some(&args)))
+            .or_else(|| message.map(str::to_string))
+    }
+
+    /// Message of an error response, looked up as `error-{code}` outside the source locale.
+    pub fn error_message(code: ErrorCode, message: String) -> String {
+        if Self::is_source() {
+            return message;
+        }
+        Self::translate(&format!("error-{}", code.code), None).unwrap_or(message)
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::{BUNDLES, CATALOGS, SOURCE_LOCALE};
+    use crate::infrastructure::error_code::ErrorCode;
+    use crate::infrastructure::validation_code::ValidationCode;
+
+    fn missing(id: &str) -> Vec<String> {
+        BUNDLES
+            .iter()
+            .filter(|(_, bundle)| bundle.get_message(id).and_then(|message| message.value()).is_none())
+            .map(|(locale, _)| format!("{id} in {locale}"))
+            .collect()
+    }
+
+    #[test]
+    fn every_error_code_is_translated() {
+        // Messages of the source locale are written in code
+        let missing: Vec<String> = ErrorCode::ALL
+            .iter()
+            .flat_map(|code| missing(&format!("error-{}", code.code)))
+            .filter(|missing| !missing.ends_with(&format!(" in {SOURCE_LOCALE}")))
+            .collect();
+        assert!(missing.is_empty(), "missing messages: {missing:?}");
+    }
+
+    #[test]
+    fn every_validation_code_is_translated() {
+        let missing: Vec<String> = ValidationCode::ALL
+            .iter()
+            .flat_map(|code| missing(&format!("validation-{code}")))
+            .collect();
+        assert!(missing.is_empty(), "missing messages: {missing:?}");
+    }
+
+    #[test]
+    fn every_validation_message_has_a_code() {
+        // `validation-length-min` and the like are variants of the `length` message
+        let unknown: Vec<String> = CATALOGS
+            .iter()
+            .flat_map(|(locale, catalog)| {
+                catalog
+                    .lines()
+                    .filter_map(|line| line.strip_prefix("validation-"))
+                    .filter_map(|line| line.split([' ', '=', '-']).next())
+                    .filter(|code| !ValidationCode::ALL.contains(code))
+                    .map(move |code| format!("validation-{code} in {locale}"))
+            })
+            .collect();
+        assert!(unknown.is_empty(), "messages of unknown codes: {unknown:?}");
+    }
+}
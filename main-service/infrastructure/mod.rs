@@ -1,4 +1,15 @@
+pub mod body_format;
+pub mod cache;
 pub mod errors;
 pub mod config;
+pub mod db_error;
+pub mod error_code;
+pub mod i18n;
+pub mod password;
+pub mod problem;
+pub mod row_stream;
 pub mod state;
-pub mod uuid;
\ No newline at end of file
+pub mod storage;
+pub mod uuid;
+pub mod validation_code;
+pub mod validators;
\ No newline at end of file
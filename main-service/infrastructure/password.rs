@@ -0,0 +1,257 @@
+use std::collections::HashSet;
+
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::SaltString;
+use argon2::{Algorithm, Argon2, Params, PasswordHash, PasswordHasher, PasswordVerifier, Version};
+use lazy_static::lazy_static;
+
+use std::borrow::Cow;
+use std::sync::OnceLock;
+
+use validator::ValidationError;
+
+use crate::infrastructure::errors::{AppError, AppResult, ValidationMessageError};
+use crate::infrastructure::validation_code::ValidationCode;
+
+lazy_static! {
+    static ref COMMON_PASSWORDS: HashSet<&'static str> = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/resources/common-passwords.txt"
+    ))
+    .lines()
+    .map(str::trim)
+    .filter(|line| !line.is_empty())
+    .collect();
+}
+
+/// Argon2 cost parameters and strength rules applied to user passwords.
+#[derive(Clone, Debug)]
+pub struct PasswordPolicy {
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+    pub min_length: usize,
+    pub max_length: usize,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self {
+            memory_kib: Params::DEFAULT_M_COST,
+            iterations: Params::DEFAULT_T_COST,
+            parallelism: Params::DEFAULT_P_COST,
+            min_length: 8,
+            max_length: 128,
+        }
+    }
+}
+
+impl PasswordPolicy {
+    fn hasher(&self) -> AppResult<Argon2<'static>> {
+        let params = Params::new(self.memory_kib, self.iterations, self.parallelism, None)
+            .map_err(|e| AppError::InternalServerErrorWithContext(e.to_string()))?;
+        Ok(Argon2::new(Algorithm::Argon2id, Version::V0x13, params))
+    }
+
+    /// Hash on the blocking pool, Argon2 takes long enough to stall the async workers.
+    pub async fn hash(&self, password: &str) -> AppResult<String> {
+        let policy = self.clone();
+        let password = password.to_string();
+        tokio::task::spawn_blocking(move || policy.hash_blocking(&password))
+            .await
+            .map_err(|e| AppError::InternalServerErrorWithContext(e.to_string()))?
+    }
+
+    /// Verify on the blocking pool, against the parameters stored in the hash, not the current policy.
+    pub async fn verify(&self, password: &str, password_hash: &str) -> AppResult<bool> {
+        let password = password.to_string();
+        let password_hash = password_hash.to_string();
+        tokio::task::spawn_blocking(move || Self::verify_blocking(&password, &password_hash))
+            .await
+            .map_err(|e| AppError::InternalServerErrorWithContext(e.to_string()))?
+    }
+
+    fn hash_blocking(&self, password: &str) -> AppResult<String> {
+        let salt = SaltString::generate(&mut OsRng);
+        let password_hash = self.hasher()?.hash_password(password.as_bytes(), &salt)?;
+        Ok(password_hash.to_string())
+    }
+
+    fn verify_blocking(password: &str, password_hash: &str) -> AppResult<bool> {
+        let parsed = PasswordHash::new(password_hash)?;
+        match Argon2::default().verify_password(password.as_bytes(), &parsed) {
+            Ok(()) => Ok(true),
+            Err(argon2::password_hash::Error::Password) => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Burn the same Argon2 work as [`PasswordPolicy::verify`] when there is no stored hash,
+    /// so a missing account cannot be told apart from a wrong password by response time.
+    pub async fn verify_dummy(&self, password: &str) -> AppResult<()> {
+        static DUMMY_HASH: OnceLock<String> = OnceLock::new();
+        let dummy_hash = match DUMMY_HASH.get() {
+            Some(hash) => hash,
+            None => {
+                let hash = self.hash("dummy password for unknown users").await?;
+                DUMMY_HASH.get_or_init(|| hash)
+            }
+        };
+        self.verify(password, dummy_hash).await?;
+        Ok(())
+    }
+
+    /// Whether the stored hash was produced with weaker settings than this policy.
+    pub fn needs_rehash(&self, password_hash: &str) -> bool {
+        let Ok(parsed) = PasswordHash::new(password_hash) else {
+            return true;
+        };
+        if parsed.algorithm != Algorithm::Argon2id.ident() {
+            return true;
+        }
+        match Params::try_from(&parsed) {
+            Ok(params) => {
+                params.m_cost() < self.memory_kib
+                    || params.t_cost() < self.iterations
+                    || params.p_cost() < self.parallelism
+            }
+            Err(_) => true,
+        }
+    }
+
+    pub fn check_strength(&self, password: &str, username: Option<&str>) -> AppResult<()> {
+        self.validate_strength(password, username).map_err(|e| {
+            let error = ValidationMessageError::of_rule("password", "/password".to_string(), &e);
+            AppError::ValidationMessageError(error)
+        })
+    }
+
+    /// The strength rules as a `validator` rule, for DTOs validating with the configured policy.
+    pub fn validate_strength(&self, password: &str, username: Option<&str>) -> Result<(), ValidationError> {
+        let length = password.chars().count();
+        if length < self.min_length {
+            let message = format!("password must be at least {} characters", self.min_length);
+            let mut error =
+                ValidationError::new(ValidationCode::PASSWORD_TOO_SHORT).with_message(Cow::from(message));
+            error.add_param(Cow::from("min"), &self.min_length);
+            Err(error)
+        } else if length > self.max_length {
+            let message = format!("password must be at most {} characters", self.max_length);
+            let mut error =
+                ValidationError::new(ValidationCode::PASSWORD_TOO_LONG).with_message(Cow::from(message));
+            error.add_param(Cow::from("max"), &self.max_length);
+            Err(error)
+        } else if let Err(error) = reject_common(password) {
+            Err(error)
+        } else if username.is_some_and(|u| password.eq_ignore_ascii_case(u)) {
+            Err(ValidationError::new(ValidationCode::PASSWORD_MATCHES_USERNAME)
+                .with_message(Cow::from("password must not match the username")))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Passwords of the bundled list of commonly used ones, compared case insensitively.
+pub fn reject_common(password: &str) -> Result<(), ValidationError> {
+    match COMMON_PASSWORDS.contains(password.to_lowercase().as_str()) {
+        true => Err(ValidationError::new(ValidationCode::PASSWORD_TOO_COMMON)
+            .with_message(Cow::from("password is too common"))),
+        false => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PasswordPolicy;
+    use crate::infrastructure::errors::AppError;
+
+    fn cheap() -> PasswordPolicy {
+        PasswordPolicy { memory_kib: 1024, iterations: 1, parallelism: 1, ..Default::default() }
+    }
+
+    #[tokio::test]
+    async fn hash_verifies_only_the_same_password() {
+        let policy = cheap();
+        let hash = policy.hash("correct horse battery").await.unwrap();
+        assert!(policy.verify("correct horse battery", &hash).await.unwrap());
+        assert!(!policy.verify("wrong horse battery", &hash).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn verify_rejects_a_malformed_hash() {
+        assert!(cheap().verify("anything", "not a phc string").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn verify_dummy_succeeds_for_any_password() {
+        let policy = cheap();
+        policy.verify_dummy("whatever").await.unwrap();
+        policy.verify_dummy("").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn needs_rehash_when_the_policy_got_stronger() {
+        let weak = cheap();
+        let hash = weak.hash("correct horse battery").await.unwrap();
+        assert!(!weak.needs_rehash(&hash));
+
+        let stronger = PasswordPolicy { iterations: 2, ..cheap() };
+        assert!(stronger.needs_rehash(&hash));
+        let more_memory = PasswordPolicy { memory_kib: 2048, ..cheap() };
+        assert!(more_memory.needs_rehash(&hash));
+    }
+
+    #[test]
+    fn needs_rehash_for_unparsable_or_other_algorithms() {
+        let policy = cheap();
+        assert!(policy.needs_rehash("plaintext"));
+        assert!(policy.needs_rehash(
+            "$argon2i$v=19$m=1024,t=1,p=1$c29tZXNhbHQ$iWh06vD8Fy27wf9npn6FXWiCX4K6pW6Ue1Bnzz07Z8A"
+        ));
+    }
+
+    #[test]
+    fn validate_strength_checks_length() {
+        let policy = PasswordPolicy { min_length: 8, max_length: 12, ..cheap() };
+        let error = policy.validate_strength("short", None).unwrap_err();
+        assert_eq!(error.code, "password_too_short");
+        assert_eq!(error.params["min"], 8);
+        let error = policy.validate_strength("far too long a password", None).unwrap_err();
+        assert_eq!(error.code, "password_too_long");
+        assert_eq!(error.params["max"], 12);
+        assert!(policy.validate_strength("just right!", None).is_ok());
+    }
+
+    #[test]
+    fn validate_strength_counts_characters_not_bytes() {
+        let policy = PasswordPolicy { min_length: 8, max_length: 8, ..cheap() };
+        assert!(policy.validate_strength("ääääöööö", None).is_ok());
+    }
+
+    #[test]
+    fn validate_strength_rejects_common_passwords_case_insensitively() {
+        let policy = cheap();
+        assert_eq!(policy.validate_strength("password", None).unwrap_err().code, "password_too_common");
+        assert_eq!(policy.validate_strength("PassWord", None).unwrap_err().code, "password_too_common");
+        assert_eq!(policy.validate_strength("12345678", None).unwrap_err().code, "password_too_common");
+    }
+
+    #[test]
+    fn validate_strength_rejects_the_username() {
+        let policy = cheap();
+        let error = policy.validate_strength("Alice.Example", Some("alice.example")).unwrap_err();
+        assert_eq!(error.code, "password_matches_username");
+        assert!(policy.validate_strength("Alice.Example", Some("bob.example")).is_ok());
+    }
+
+    #[test]
+    fn check_strength_reports_the_password_field() {
+        let Err(AppError::ValidationMessageError(error)) = cheap().check_strength("short", None) else {
+            panic!("expected a validation error");
+        };
+        assert_eq!(error.field, "password");
+        assert_eq!(error.pointer, "/password");
+        assert_eq!(error.code.as_deref(), Some("password_too_short"));
+    }
+}
@@ -0,0 +1,98 @@
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::Response;
+use serde::{Deserialize, Serialize};
+use crate::infrastructure::body_format::media_ranges;
+use crate::infrastructure::error_code::ErrorCode;
+use crate::infrastructure::errors::ValidationMessageError;
+use crate::infrastructure::i18n::I18n;
+
+pub const PROBLEM_JSON: &str = "application/problem+json";
+
+/// Shape of error bodies. `Envelope` keeps the `BaseResponse` / `{"errors": ...}` bodies and
+/// still answers with problem details when the client asks for them in `Accept`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorFormat {
+    #[default]
+    Envelope,
+    Problem,
+}
+
+/// RFC 7807 problem details. Every error response carries one as an extension so the
+/// `problem_details` middleware can render it regardless of where the error came from.
+#[derive(Clone, Debug, Serialize)]
+pub struct Problem {
+    #[serde(rename = "type")]
+    pub type_uri: String,
+    pub title: String,
+    pub status: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+    /// Extension member with the stable `ErrorCode`.
+    pub code: String,
+    /// Extension member pointing at the logged cause of server errors.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>,
+    /// Extension member listing field failures of validation problems.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<ValidationMessageError>,
+}
+
+impl Problem {
+    pub fn new(status: StatusCode, code: ErrorCode, detail: impl Into<String>) -> Self {
+        Problem {
+            detail: Some(detail.into()),
+            code: code.code.to_string(),
+            ..Self::of_status(status)
+        }
+    }
+
+    /// A problem without further detail, titled after the status.
+    pub fn of_status(status: StatusCode) -> Self {
+        Problem {
+            code: ErrorCode::HTTP_ERROR.code.to_string(),
+            type_uri: "about:blank".to_string(),
+            title: status.canonical_reason().unwrap_or("Error").to_string(),
+            status: status.as_u16(),
+            detail: None,
+            instance: None,
+            correlation_id: None,
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn validation(
+        status: StatusCode,
+        code: ErrorCode,
+        errors: Vec<ValidationMessageError>,
+    ) -> Self {
+        Problem {
+            errors,
+            ..Self::new(
+                status,
+                code,
+                I18n::error_message(code, "request contains invalid fields".to_string()),
+            )
+        }
+    }
+
+    pub fn with_correlation_id(mut self, correlation_id: String) -> Self {
+        self.correlation_id = Some(correlation_id);
+        self
+    }
+
+    /// Attach the problem to an already rendered response.
+    pub fn attach(self, mut response: Response) -> Response {
+        response.extensions_mut().insert(self);
+        response
+    }
+
+    /// Whether the `Accept` header lists `application/problem+json` with a non zero quality.
+    pub fn is_accepted(headers: &HeaderMap) -> bool {
+        media_ranges(headers)
+            .iter()
+            .any(|(media_type, quality)| media_type.eq_ignore_ascii_case(PROBLEM_JSON) && *quality > 0.0)
+    }
+}
\ No newline at end of file
@@ -0,0 +1,267 @@
+use std::convert::Infallible;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::body::{Body, Bytes};
+use axum::http::header::{CONTENT_DISPOSITION, CONTENT_TYPE};
+use axum::response::{IntoResponse, Response};
+use sea_orm::{DatabaseConnection, Selector, SelectorTrait};
+use serde::de::{MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::StreamExt;
+use validator::Validate;
+
+use crate::dto::base::BaseResponse;
+use crate::infrastructure::errors::{AppError, AppResult};
+use crate::infrastructure::i18n::I18n;
+
+/// Encoded rows buffered ahead of the client. The query only advances as the client reads.
+const BUFFERED_ROWS: usize = 64;
+/// Longest wait for the client to make room in the buffer. Exports hold a pooled connection,
+/// so a client that stops reading is dropped instead of pinning it.
+const SEND_TIMEOUT: Duration = Duration::from_secs(30);
+/// Longest an export may hold its connection, however fast the client reads.
+const EXPORT_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StreamFormat {
+    #[default]
+    Ndjson,
+    Csv,
+}
+
+impl StreamFormat {
+    fn content_type(self) -> &'static str {
+        match self {
+            Self::Ndjson => "application/x-ndjson",
+            Self::Csv => "text/csv; charset=utf-8",
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Ndjson => "ndjson",
+            Self::Csv => "csv",
+        }
+    }
+}
+
+/// `?format=ndjson` (default) or `?format=csv` of export endpoints.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Validate)]
+pub struct StreamParams {
+    #[serde(default)]
+    pub format: StreamFormat,
+}
+
+/// Streams query results row by row instead of materializing them in a `BaseResponse`.
+///
+/// The status and headers are sent before the first row is read, so a failure midway ends
+/// the body with an error trailer: a last `{"error": {...}}` line for NDJSON and a last
+/// `#error,<code>,<correlation_id>,<message>` record for CSV. Clients must check for it.
+/// Exports running longer than `EXPORT_TIMEOUT` end with such a trailer too, clients not reading
+/// for `SEND_TIMEOUT` are cut off without one.
+pub struct RowStream;
+
+impl RowStream {
+    /// Respond with the rows of `selector` mapped by `map`, as an attachment `{name}.{ext}`.
+    pub fn response<S, R, F>(
+        db: Arc<DatabaseConnection>,
+        selector: Selector<S>,
+        format: StreamFormat,
+        name: &str,
+        map: F,
+    ) -> Response
+    where
+        S: SelectorTrait + Send + Sync + 'static,
+        S::Item: Send,
+        R: Serialize,
+        F: Fn(S::Item) -> AppResult<R> + Send + Sync + 'static,
+    {
+        let (sender, receiver) = mpsc::channel::<Bytes>(BUFFERED_ROWS);
+        let export = async move {
+            let mut encoder = RowEncoder::new(format);
+            let rows = async {
+                let mut rows = selector.stream(&*db).await?;
+                while let Some(row) = rows.next().await {
+                    let chunk = encoder.row(&map(row?)?)?;
+                    if sender.send_timeout(chunk, SEND_TIMEOUT).await.is_err() {
+                        // The client went away or stopped reading, stop reading too
+                        return Ok(());
+                    }
+                }
+                Ok::<(), AppError>(())
+            };
+            // Dropping the row stream on timeout returns the connection to the pool
+            let result = match tokio::time::timeout(EXPORT_TIMEOUT, rows).await {
+                Ok(result) => result,
+                Err(_) => Err(AppError::InternalServerErrorWithContext(format!(
+                    "export did not finish within {} seconds",
+                    EXPORT_TIMEOUT.as_secs()
+                ))),
+            };
+            if let Err(e) = result {
+                let _ = sender.send_timeout(encoder.trailer(&e), SEND_TIMEOUT).await;
+            }
+        };
+        // Trailers are rendered in the locale and with the error exposure of the request
+        let locale = I18n::current();
+        let expose = AppError::exposes_internal_errors();
+        tokio::spawn(AppError::scope_internal_errors(expose, I18n::scope(locale, export)));
+
+        let body = Body::from_stream(ReceiverStream::new(receiver).map(Ok::<_, Infallible>));
+        let disposition = format!("attachment; filename=\"{name}.{}\"", format.extension());
+        (
+            [
+                (CONTENT_TYPE, format.content_type().to_string()),
+                (CONTENT_DISPOSITION, disposition),
+            ],
+            body,
+        )
+            .into_response()
+    }
+}
+
+struct RowEncoder {
+    format: StreamFormat,
+    /// CSV columns, taken from the fields of the first row.
+    columns: Option<Vec<String>>,
+}
+
+impl RowEncoder {
+    fn new(format: StreamFormat) -> Self {
+        RowEncoder { format, columns: None }
+    }
+
+    fn row(&mut self, row: &impl Serialize) -> AppResult<Bytes> {
+        match self.format {
+            StreamFormat::Ndjson => {
+                let mut line = serde_json::to_vec(row)?;
+                line.push(b'\n');
+                Ok(Bytes::from(line))
+            }
+            StreamFormat::Csv => {
+                let Fields(fields) = serde_json::from_slice(&serde_json::to_vec(row)?)?;
+                let mut records = Vec::new();
+                if self.columns.is_none() {
+                    let columns: Vec<String> = fields.iter().map(|(name, _)| name.clone()).collect();
+                    records.push(columns.clone());
+                    self.columns = Some(columns);
+                }
+                let columns = self.columns.as_deref().unwrap_or_default();
+                let record = columns.iter().map(|column| {
+                    csv_cell(fields.iter().find(|(name, _)| name == column).map(|(_, value)| value))
+                });
+                records.push(record.collect());
+                csv_records(&records)
+            }
+        }
+    }
+
+    fn trailer(&self, error: &AppError) -> Bytes {
+        let code = error.code();
+        let correlation_id = AppError::correlate(error);
+        let message = I18n::error_message(code, AppError::public_message(error.to_string()));
+        match self.format {
+            StreamFormat::Ndjson => {
+                let trailer = BaseResponse::<()>::error(message, code.code, Some(code.status))
+                    .with_correlation_id(correlation_id);
+                let mut line = serde_json::to_vec(&trailer).unwrap_or_default();
+                line.push(b'\n');
+                Bytes::from(line)
+            }
+            StreamFormat::Csv => {
+                let record = vec!["#error".to_string(), code.code.to_string(), correlation_id, message];
+                csv_records(&[record]).unwrap_or_default()
+            }
+        }
+    }
+}
+
+/// Fields of a row object in serialization order, which `serde_json::Map` would sort by name.
+struct Fields(Vec<(String, Value)>);
+
+impl<'de> Deserialize<'de> for Fields {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FieldsVisitor;
+
+        impl<'de> Visitor<'de> for FieldsVisitor {
+            type Value = Fields;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a row object")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Fields, A::Error> {
+                let mut fields = Vec::new();
+                while let Some(field) = map.next_entry()? {
+                    fields.push(field);
+                }
+                Ok(Fields(fields))
+            }
+        }
+
+        deserializer.deserialize_map(FieldsVisitor)
+    }
+}
+
+/// Text cells starting like a formula are prefixed with `'`, so spreadsheets opening the
+/// export show them as text instead of evaluating them (CSV injection).
+fn csv_cell(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(text)) if text.starts_with(['=', '+', '-', '@']) => format!("'{text}"),
+        Some(Value::String(text)) => text.clone(),
+        Some(other) => other.to_string(),
+    }
+}
+
+fn csv_records(records: &[Vec<String>]) -> AppResult<Bytes> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for record in records {
+        writer
+            .write_record(record)
+            .map_err(|e| AppError::InternalServerErrorWithContext(e.to_string()))?;
+    }
+    let bytes = writer
+        .into_inner()
+        .map_err(|e| AppError::InternalServerErrorWithContext(e.to_string()))?;
+    Ok(Bytes::from(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{csv_cell, RowEncoder, StreamFormat};
+    use serde_json::json;
+
+    #[test]
+    fn csv_cells_starting_like_formulas_are_quoted() {
+        for (value, cell) in [
+            ("=HYPERLINK(\"http://x\")", "'=HYPERLINK(\"http://x\")"),
+            ("+49 30 123456", "'+49 30 123456"),
+            ("-2+3", "'-2+3"),
+            ("@SUM(A1)", "'@SUM(A1)"),
+            ("a=b", "a=b"),
+            ("", ""),
+        ] {
+            assert_eq!(csv_cell(Some(&json!(value))), cell);
+        }
+        // Only text is evaluated as a formula, numbers are written as they are
+        assert_eq!(csv_cell(Some(&json!(-1))), "-1");
+        assert_eq!(csv_cell(Some(&json!(null))), "");
+        assert_eq!(csv_cell(None), "");
+    }
+
+    #[test]
+    fn csv_rows_keep_the_columns_of_the_first_row() {
+        let mut encoder = RowEncoder::new(StreamFormat::Csv);
+        let first = encoder.row(&json!({ "name": "=1+1", "size": 2 })).unwrap();
+        let second = encoder.row(&json!({ "size": 3, "extra": true })).unwrap();
+        assert_eq!(first, "name,size\n'=1+1,2\n");
+        assert_eq!(second, ",3\n");
+    }
+}
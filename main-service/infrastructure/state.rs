@@ -1,21 +1,50 @@
 use std::sync::Arc;
 use axum::extract::FromRef;
+use entity::user_session;
 use sea_orm::DatabaseConnection;
+use uuid::Uuid;
+use crate::infrastructure::cache::UserPrincipalCache;
 use crate::infrastructure::config::Config;
+use crate::infrastructure::storage::{LocalStorage, Storage};
 
 #[derive(Clone, Debug, FromRef)]
 pub struct AppState {
     pub db: Arc<DatabaseConnection>,
     pub config: Arc<Config>,
     pub cache_text: Arc<moka::future::Cache<String, String>>,
+    pub user_cache: Arc<UserPrincipalCache>,
+    pub session_cache: Arc<moka::future::Cache<Uuid, user_session::Model>>,
+    pub pairing_cache: Arc<moka::future::Cache<String, Uuid>>,
+    pub storage: Arc<dyn Storage>,
+    #[from_ref(skip)]
+    pub private_storage: Arc<dyn Storage>,
 }
 
 impl AppState {
     pub fn init(db: Arc<DatabaseConnection>, config: Arc<Config>) -> Self {
+        let user_cache = UserPrincipalCache::new(
+            10_000,
+            std::time::Duration::from_secs(config.user_cache_ttl),
+        );
+        let session_cache = moka::future::Cache::builder()
+            .max_capacity(10_000)
+            .time_to_live(std::time::Duration::from_secs(config.user_cache_ttl))
+            .build();
+        let pairing_cache = moka::future::Cache::builder()
+            .max_capacity(10_000)
+            .time_to_live(std::time::Duration::from_secs(config.device_pairing_ttl))
+            .build();
+        let storage = LocalStorage::new(&config.storage_path, &config.storage_base_url);
+        let private_storage = LocalStorage::new(&config.private_storage_path, "");
         AppState {
             db,
             config,
             cache_text: Arc::new(Self::create_cache()),
+            user_cache: Arc::new(user_cache),
+            session_cache: Arc::new(session_cache),
+            pairing_cache: Arc::new(pairing_cache),
+            storage: Arc::new(storage),
+            private_storage: Arc::new(private_storage),
         }
     }
 
@@ -0,0 +1,81 @@
+use std::fmt::Debug;
+use std::future::Future;
+use std::path::{Component, Path, PathBuf};
+use std::pin::Pin;
+use crate::infrastructure::errors::{AppError, AppResult};
+
+pub type StorageFuture<'a, T> = Pin<Box<dyn Future<Output = AppResult<T>> + Send + 'a>>;
+
+/// Object storage for user uploaded files, addressed by slash separated keys.
+pub trait Storage: Debug + Send + Sync {
+    fn put<'a>(&'a self, key: &'a str, bytes: Vec<u8>) -> StorageFuture<'a, ()>;
+    fn get<'a>(&'a self, key: &'a str) -> StorageFuture<'a, Vec<u8>>;
+    fn delete<'a>(&'a self, key: &'a str) -> StorageFuture<'a, ()>;
+    /// Public URL the stored object is served from.
+    fn url(&self, key: &str) -> String;
+}
+
+/// Stores objects on the local filesystem, served by the router under `base_url`.
+/// Private storages are never mounted and their objects only leave through handlers.
+#[derive(Clone, Debug)]
+pub struct LocalStorage {
+    root: PathBuf,
+    base_url: String,
+}
+
+impl LocalStorage {
+    pub fn new(root: impl Into<PathBuf>, base_url: &str) -> Self {
+        Self {
+            root: root.into(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    fn path(&self, key: &str) -> AppResult<PathBuf> {
+        let relative = Path::new(key);
+        // Keys are generated by the server, anything escaping the root is a bug
+        if !relative.components().all(|c| matches!(c, Component::Normal(_))) {
+            return Err(AppError::InternalServerErrorWithContext(format!(
+                "invalid storage key {key}"
+            )));
+        }
+        Ok(self.root.join(relative))
+    }
+}
+
+impl Storage for LocalStorage {
+    fn put<'a>(&'a self, key: &'a str, bytes: Vec<u8>) -> StorageFuture<'a, ()> {
+        Box::pin(async move {
+            let path = self.path(key)?;
+            if let Some(parent) = path.parent() {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+            tokio::fs::write(path, bytes).await?;
+            Ok(())
+        })
+    }
+
+    fn get<'a>(&'a self, key: &'a str) -> StorageFuture<'a, Vec<u8>> {
+        Box::pin(async move {
+            match tokio::fs::read(self.path(key)?).await {
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                    Err(AppError::NotFound(format!("{key} does not exist")))
+                }
+                result => Ok(result?),
+            }
+        })
+    }
+
+    fn delete<'a>(&'a self, key: &'a str) -> StorageFuture<'a, ()> {
+        Box::pin(async move {
+            match tokio::fs::remove_file(self.path(key)?).await {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+                _ => Ok(()),
+            }
+        })
+    }
+
+    fn url(&self, key: &str) -> String {
+        format!("{}/{}", self.base_url, key)
+    }
+}
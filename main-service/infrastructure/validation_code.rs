@@ -0,0 +1,134 @@
+/// Codes of failed validation rules. Each is sent as the `code` of a field error and looked up
+/// as `validation-{code}` in the message catalogs, so a published code must not be renamed.
+pub struct ValidationCode;
+
+// The `validator` derive emits its built-in rule names itself, so those constants are only read
+// through `ALL`.
+#[allow(dead_code)]
+impl ValidationCode {
+    // Rules of `#[validate(...)]` attributes, named by the `validator` crate
+    pub const REQUIRED: &'static str = "required";
+    pub const LENGTH: &'static str = "length";
+    pub const RANGE: &'static str = "range";
+    pub const EMAIL: &'static str = "email";
+    pub const URL: &'static str = "url";
+    pub const REGEX: &'static str = "regex";
+    pub const MUST_MATCH: &'static str = "must_match";
+    pub const CONTAINS: &'static str = "contains";
+    pub const DOES_NOT_CONTAIN: &'static str = "does_not_contain";
+    pub const CREDIT_CARD: &'static str = "credit_card";
+    pub const NON_CONTROL_CHARACTER: &'static str = "non_control_character";
+
+    // Generic failures and database constraints
+    pub const INVALID: &'static str = "invalid";
+    pub const UNIQUE: &'static str = "unique";
+    pub const FOREIGN_KEY: &'static str = "foreign_key";
+
+    // Passwords
+    pub const PASSWORD_INCORRECT: &'static str = "password_incorrect";
+    pub const PASSWORD_TOO_SHORT: &'static str = "password_too_short";
+    pub const PASSWORD_TOO_LONG: &'static str = "password_too_long";
+    pub const PASSWORD_TOO_COMMON: &'static str = "password_too_common";
+    pub const PASSWORD_MATCHES_USERNAME: &'static str = "password_matches_username";
+
+    // Uploads
+    pub const IMAGE_TYPE: &'static str = "image_type";
+    pub const IMAGE_UNDECODABLE: &'static str = "image_undecodable";
+    pub const FILE_TOO_LARGE: &'static str = "file_too_large";
+    pub const FILE_TYPE: &'static str = "file_type";
+    pub const FILE_UNEXPECTED: &'static str = "file_unexpected";
+    pub const MULTIPART_FIELDS: &'static str = "multipart_fields";
+
+    // List queries
+    pub const OFFSET_WITH_PAGE: &'static str = "offset_with_page";
+    pub const NOT_SORTABLE: &'static str = "not_sortable";
+    pub const MALFORMED_FILTER: &'static str = "malformed_filter";
+    pub const NOT_FILTERABLE: &'static str = "not_filterable";
+    pub const BOOLEAN: &'static str = "boolean";
+    pub const UNKNOWN_OPERATOR: &'static str = "unknown_operator";
+    pub const TEXT_OPERATOR: &'static str = "text_operator";
+    pub const NUMBER: &'static str = "number";
+    pub const TYPE_MISMATCH: &'static str = "type_mismatch";
+    pub const CURSOR_INVALID: &'static str = "cursor_invalid";
+    pub const CURSOR_SORT_MISMATCH: &'static str = "cursor_sort_mismatch";
+    pub const CURSOR_FILTER_MISMATCH: &'static str = "cursor_filter_mismatch";
+
+    // Rules of `crate::infrastructure::validators`
+    pub const COUNTRY: &'static str = "country";
+    pub const LOCALE: &'static str = "locale";
+    pub const TIMEZONE: &'static str = "timezone";
+    pub const UUID: &'static str = "uuid";
+    pub const PHONE: &'static str = "phone";
+    pub const HEX_COLOR: &'static str = "hex_color";
+    pub const BBOX: &'static str = "bbox";
+    pub const COORDINATE_RANGE: &'static str = "coordinate_range";
+    pub const POSITION_INVALID: &'static str = "position_invalid";
+    pub const LINE_TOO_SHORT: &'static str = "line_too_short";
+    pub const RING_INVALID: &'static str = "ring_invalid";
+
+    /// Every code the API can send, each needs a message in every catalog.
+    pub const ALL: &'static [&'static str] = &[
+        Self::REQUIRED,
+        Self::LENGTH,
+        Self::RANGE,
+        Self::EMAIL,
+        Self::URL,
+        Self::REGEX,
+        Self::MUST_MATCH,
+        Self::CONTAINS,
+        Self::DOES_NOT_CONTAIN,
+        Self::CREDIT_CARD,
+        Self::NON_CONTROL_CHARACTER,
+        Self::INVALID,
+        Self::UNIQUE,
+        Self::FOREIGN_KEY,
+        Self::PASSWORD_INCORRECT,
+        Self::PASSWORD_TOO_SHORT,
+        Self::PASSWORD_TOO_LONG,
+        Self::PASSWORD_TOO_COMMON,
+        Self::PASSWORD_MATCHES_USERNAME,
+        Self::IMAGE_TYPE,
+        Self::IMAGE_UNDECODABLE,
+        Self::FILE_TOO_LARGE,
+        Self::FILE_TYPE,
+        Self::FILE_UNEXPECTED,
+        Self::MULTIPART_FIELDS,
+        Self::OFFSET_WITH_PAGE,
+        Self::NOT_SORTABLE,
+        Self::MALFORMED_FILTER,
+        Self::NOT_FILTERABLE,
+        Self::BOOLEAN,
+        Self::UNKNOWN_OPERATOR,
+        Self::TEXT_OPERATOR,
+        Self::NUMBER,
+        Self::TYPE_MISMATCH,
+        Self::CURSOR_INVALID,
+        Self::CURSOR_SORT_MISMATCH,
+        Self::CURSOR_FILTER_MISMATCH,
+        Self::COUNTRY,
+        Self::LOCALE,
+        Self::TIMEZONE,
+        Self::UUID,
+        Self::PHONE,
+        Self::HEX_COLOR,
+        Self::BBOX,
+        Self::COORDINATE_RANGE,
+        Self::POSITION_INVALID,
+        Self::LINE_TOO_SHORT,
+        Self::RING_INVALID,
+    ];
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ValidationCode;
+    use std::collections::HashSet;
+
+    #[test]
+    fn codes_are_unique() {
+        let mut seen = HashSet::new();
+        for code in ValidationCode::ALL {
+            assert!(seen.insert(code), "validation code {code} is listed twice");
+        }
+    }
+}
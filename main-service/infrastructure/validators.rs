@@ -0,0 +1,342 @@
+//! Rules for `#[validate(custom(function = ...))]`. Each failure carries a stable code that
+//! the message catalogs translate as `validation-{code}`, so DTOs only name the rule.
+
+use std::borrow::Cow;
+use std::str::FromStr;
+
+use geojson::{Geometry, PolygonType, Position, Value};
+use lazy_static::lazy_static;
+use regex::Regex;
+use unic_langid::LanguageIdentifier;
+use validator::ValidationError;
+
+use crate::infrastructure::password;
+use crate::infrastructure::validation_code::ValidationCode;
+
+lazy_static! {
+    // E.164 number such as `+4930123456`, at most 15 digits without the leading `+`
+    static ref PHONE_REGEX: Regex = Regex::new(r"^\+[1-9][0-9]{1,14}$").unwrap();
+    // IANA time zone name such as `UTC` or `America/Argentina/Buenos_Aires`
+    static ref TIMEZONE_REGEX: Regex = Regex::new(r"^(UTC|[A-Za-z]+(/[A-Za-z0-9_+\-]+)+)$").unwrap();
+    // `#rgb`, `#rgba`, `#rrggbb` or `#rrggbbaa`
+    static ref HEX_COLOR_REGEX: Regex =
+        Regex::new(r"^#([0-9a-fA-F]{3,4}|[0-9a-fA-F]{6}|[0-9a-fA-F]{8})$").unwrap();
+}
+
+/// Officially assigned ISO 3166-1 alpha-2 codes.
+const COUNTRY_CODES: &str = "\
+    AD AE AF AG AI AL AM AO AQ AR AS AT AU AW AX AZ \
+    BA BB BD BE BF BG BH BI BJ BL BM BN BO BQ BR BS BT BV BW BY BZ \
+    CA CC CD CF CG CH CI CK CL CM CN CO CR CU CV CW CX CY CZ \
+    DE DJ DK DM DO DZ \
+    EC EE EG EH ER ES ET \
+    FI FJ FK FM FO FR \
+    GA GB GD GE GF GG GH GI GL GM GN GP GQ GR GS GT GU GW GY \
+    HK HM HN HR HT HU \
+    ID IE IL IM IN IO IQ IR IS IT \
+    JE JM JO JP \
+    KE KG KH KI KM KN KP KR KW KY KZ \
+    LA LB LC LI LK LR LS LT LU LV LY \
+    MA MC MD ME MF MG MH MK ML MM MN MO MP MQ MR MS MT MU MV MW MX MY MZ \
+    NA NC NE NF NG NI NL NO NP NR NU NZ \
+    OM \
+    PA PE PF PG PH PK PL PM PN PR PS PT PW PY \
+    QA \
+    RE RO RS RU RW \
+    SA SB SC SD SE SG SH SI SJ SK SL SM SN SO SR SS ST SV SX SY SZ \
+    TC TD TF TG TH TJ TK TL TM TN TO TR TT TV TW TZ \
+    UA UG UM US UY UZ \
+    VA VC VE VG VI VN VU \
+    WF WS \
+    YE YT \
+    ZA ZM ZW";
+
+fn invalid(code: &'static str) -> ValidationError {
+    ValidationError::new(code)
+}
+
+/// The strength rules every `PasswordPolicy` shares, code `password_too_common`. Length and
+/// username depend on the configured policy, routes check them with `check_strength`.
+pub fn strong_password(password: &str) -> Result<(), ValidationError> {
+    password::reject_common(password)
+}
+
+/// A hyphenated UUID such as `0190a1b2-...`, code `uuid`.
+pub fn uuid(value: &str) -> Result<(), ValidationError> {
+    match value.len() == 36 && uuid::Uuid::parse_str(value).is_ok() {
+        true => Ok(()),
+        false => Err(invalid(ValidationCode::UUID)),
+    }
+}
+
+/// A phone number in E.164 format, code `phone`.
+pub fn phone(value: &str) -> Result<(), ValidationError> {
+    match PHONE_REGEX.is_match(value) {
+        true => Ok(()),
+        false => Err(invalid(ValidationCode::PHONE)),
+    }
+}
+
+/// An upper case ISO 3166-1 alpha-2 country code, code `country`.
+pub fn country(value: &str) -> Result<(), ValidationError> {
+    let known = value.len() == 2 && COUNTRY_CODES.split_whitespace().any(|code| code == value);
+    match known {
+        true => Ok(()),
+        false => Err(invalid(ValidationCode::COUNTRY)),
+    }
+}
+
+/// A BCP 47 language tag such as `en`, `pt-BR` or `zh-Hant-TW` whose region, if any, is a
+/// known country, code `locale`.
+pub fn locale(value: &str) -> Result<(), ValidationError> {
+    let valid = !value.is_empty()
+        && LanguageIdentifier::from_str(value).is_ok_and(|locale| {
+            let language = locale.language.as_str();
+            let region = locale.region.map(|region| region.as_str().to_string());
+            language.len() <= 3
+                && region.is_none_or(|region| {
+                    // UN M.49 area codes such as `419` are allowed as well
+                    region.bytes().all(|b| b.is_ascii_digit()) || country(&region).is_ok()
+                })
+        });
+    match valid {
+        true => Ok(()),
+        false => Err(invalid(ValidationCode::LOCALE)),
+    }
+}
+
+/// An IANA time zone name, code `timezone`.
+pub fn timezone(value: &str) -> Result<(), ValidationError> {
+    match value.len() <= 64 && TIMEZONE_REGEX.is_match(value) {
+        true => Ok(()),
+        false => Err(invalid(ValidationCode::TIMEZONE)),
+    }
+}
+
+/// A CSS style hex color such as `#1e90ff`, code `hex_color`.
+pub fn hex_color(value: &str) -> Result<(), ValidationError> {
+    match HEX_COLOR_REGEX.is_match(value) {
+        true => Ok(()),
+        false => Err(invalid(ValidationCode::HEX_COLOR)),
+    }
+}
+
+/// A GeoJSON bounding box `[west, south, east, north]`, optionally with the minimum and maximum
+/// altitude after the latitudes. `west > east` crosses the antimeridian, see RFC 7946 5.2.
+/// Codes `bbox` for the shape and order, `coordinate_range` for out of range values.
+pub fn bbox(value: &[f64]) -> Result<(), ValidationError> {
+    let (west, south, east, north) = match *value {
+        [west, south, east, north] => (west, south, east, north),
+        [west, south, low, east, north, high] if low <= high => (west, south, east, north),
+        _ => return Err(invalid(ValidationCode::BBOX)),
+    };
+    check_position(west, south)?;
+    check_position(east, north)?;
+    match south <= north {
+        true => Ok(()),
+        false => Err(invalid(ValidationCode::BBOX)),
+    }
+}
+
+/// A GeoJSON geometry with finite longitudes in -180..=180 and latitudes in -90..=90, lines of
+/// at least two positions and closed polygon rings of at least four. Codes `coordinate_range`,
+/// `position_invalid`, `line_too_short` and `ring_invalid`.
+pub fn geometry(value: &Geometry) -> Result<(), ValidationError> {
+    if let Some(bbox) = &value.bbox {
+        self::bbox(bbox)?;
+    }
+    check_geometry(&value.value)
+}
+
+fn check_geometry(value: &Value) -> Result<(), ValidationError> {
+    match value {
+        Value::Point(position) => check_positions(std::slice::from_ref(position)),
+        Value::MultiPoint(positions) => check_positions(positions),
+        Value::LineString(line) => check_line(line),
+        Value::MultiLineString(lines) => lines.iter().try_for_each(|line| check_line(line)),
+        Value::Polygon(polygon) => check_polygon(polygon),
+        Value::MultiPolygon(polygons) => polygons.iter().try_for_each(check_polygon),
+        Value::GeometryCollection(geometries) => geometries.iter().try_for_each(geometry),
+    }
+}
+
+fn check_line(line: &[Position]) -> Result<(), ValidationError> {
+    if line.len() < 2 {
+        return Err(invalid(ValidationCode::LINE_TOO_SHORT));
+    }
+    check_positions(line)
+}
+
+fn check_polygon(polygon: &PolygonType) -> Result<(), ValidationError> {
+    if polygon.is_empty() {
+        return Err(invalid(ValidationCode::RING_INVALID));
+    }
+    for ring in polygon {
+        if ring.len() < 4 || ring.first() != ring.last() {
+            return Err(invalid(ValidationCode::RING_INVALID));
+        }
+        check_positions(ring)?;
+    }
+    Ok(())
+}
+
+fn check_positions(positions: &[Position]) -> Result<(), ValidationError> {
+    for position in positions {
+        match position.as_slice() {
+            [longitude, latitude] | [longitude, latitude, _] => check_position(*longitude, *latitude)?,
+            _ => return Err(invalid(ValidationCode::POSITION_INVALID)),
+        }
+    }
+    Ok(())
+}
+
+fn check_position(longitude: f64, latitude: f64) -> Result<(), ValidationError> {
+    // `contains` is false for NaN, so non-finite values are out of range as well
+    if !(-180.0..=180.0).contains(&longitude) || !(-90.0..=90.0).contains(&latitude) {
+        let mut error = invalid(ValidationCode::COORDINATE_RANGE);
+        error.add_param(Cow::from("longitude"), &longitude);
+        error.add_param(Cow::from("latitude"), &latitude);
+        return Err(error);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bbox, country, geometry, hex_color, locale, phone, strong_password, timezone, uuid};
+    use geojson::{Geometry, Value};
+
+    #[test]
+    fn locale_accepts_language_script_and_region_tags() {
+        for value in ["en", "de-DE", "pt-BR", "zh-Hant-TW", "es-419", "fil"] {
+            assert!(locale(value).is_ok(), "{value} was rejected");
+        }
+    }
+
+    #[test]
+    fn locale_rejects_unknown_regions_and_malformed_tags() {
+        for value in ["", "en-ZZ", "en-XX", "english", "en--US", "de-DE-", "es-41"] {
+            let error = locale(value).expect_err(value);
+            assert_eq!(error.code, "locale");
+        }
+    }
+
+    #[test]
+    fn country_needs_an_assigned_upper_case_alpha_2_code() {
+        assert!(country("DE").is_ok());
+        for value in ["de", "DEU", "ZZ", "UK", ""] {
+            let error = country(value).expect_err(value);
+            assert_eq!(error.code, "country");
+        }
+    }
+
+    #[test]
+    fn timezone_accepts_iana_names_only() {
+        for value in ["UTC", "Europe/Berlin", "America/Argentina/Buenos_Aires", "Etc/GMT+5"] {
+            assert!(timezone(value).is_ok(), "{value} was rejected");
+        }
+        for value in ["", "Berlin", "Europe/", "../etc/passwd", "Europe/Berlin Mitte"] {
+            let error = timezone(value).expect_err(value);
+            assert_eq!(error.code, "timezone");
+        }
+        assert!(timezone(&format!("Europe/{}", "A".repeat(64))).is_err());
+    }
+
+    #[test]
+    fn strong_password_rejects_common_passwords_only() {
+        assert!(strong_password("correct horse battery staple").is_ok());
+        // Length is up to the configured policy
+        assert!(strong_password("x7!").is_ok());
+        let error = strong_password("PASSWORD").expect_err("common password");
+        assert_eq!(error.code, "password_too_common");
+    }
+
+    #[test]
+    fn uuid_needs_the_hyphenated_form() {
+        assert!(uuid("0190a1b2-c3d4-7e5f-8a6b-7c8d9e0f1a2b").is_ok());
+        let braced = "{0190a1b2-c3d4-7e5f-8a6b-7c8d9e0f1a2b}";
+        for value in ["", "0190a1b2c3d47e5f8a6b7c8d9e0f1a2b", braced, "not-a-uuid"] {
+            let error = uuid(value).expect_err(value);
+            assert_eq!(error.code, "uuid");
+        }
+    }
+
+    #[test]
+    fn phone_needs_e164() {
+        for value in ["+4930123456", "+12025550123", "+999999999999999"] {
+            assert!(phone(value).is_ok(), "{value} was rejected");
+        }
+        for value in ["", "030123456", "+0301234", "+1", "+49 30 123456", "+1234567890123456"] {
+            let error = phone(value).expect_err(value);
+            assert_eq!(error.code, "phone");
+        }
+    }
+
+    #[test]
+    fn hex_color_accepts_three_four_six_and_eight_digits() {
+        for value in ["#fff", "#ffff", "#1e90FF", "#1e90ff80"] {
+            assert!(hex_color(value).is_ok(), "{value} was rejected");
+        }
+        for value in ["", "fff", "#ff", "#fffff", "#1e90ff8", "#ggg", "red"] {
+            let error = hex_color(value).expect_err(value);
+            assert_eq!(error.code, "hex_color");
+        }
+    }
+
+    #[test]
+    fn bbox_checks_shape_order_and_ranges() {
+        assert!(bbox(&[-10.0, 40.0, 10.0, 50.0]).is_ok());
+        // Crossing the antimeridian
+        assert!(bbox(&[170.0, -20.0, -170.0, 20.0]).is_ok());
+        assert!(bbox(&[-10.0, 40.0, 0.0, 10.0, 50.0, 100.0]).is_ok());
+        let invalid: [&[f64]; 4] = [
+            &[],
+            &[1.0, 2.0, 3.0],
+            &[-10.0, 50.0, 10.0, 40.0],
+            &[-10.0, 40.0, 100.0, 10.0, 50.0, 0.0],
+        ];
+        for value in invalid {
+            assert_eq!(bbox(value).expect_err("bbox").code, "bbox", "{value:?}");
+        }
+        let error = bbox(&[-190.0, 40.0, 10.0, 50.0]).expect_err("out of range");
+        assert_eq!(error.code, "coordinate_range");
+        assert_eq!(error.params["longitude"], -190.0);
+    }
+
+    #[test]
+    fn geometry_checks_positions_lines_and_rings() {
+        let ring = vec![vec![0.0, 0.0], vec![1.0, 0.0], vec![1.0, 1.0], vec![0.0, 0.0]];
+        let open_ring = vec![vec![0.0, 0.0], vec![1.0, 0.0], vec![1.0, 1.0], vec![0.0, 1.0]];
+        let valid = [
+            Value::Point(vec![13.4, 52.5]),
+            Value::Point(vec![13.4, 52.5, 34.0]),
+            Value::LineString(vec![vec![0.0, 0.0], vec![1.0, 1.0]]),
+            Value::Polygon(vec![ring.clone()]),
+            Value::GeometryCollection(vec![
+                Geometry::new(Value::MultiPolygon(vec![vec![ring.clone()]])),
+            ]),
+        ];
+        for value in valid {
+            assert!(geometry(&Geometry::new(value.clone())).is_ok(), "{value:?} was rejected");
+        }
+
+        let invalid = [
+            (Value::Point(vec![13.4]), "position_invalid"),
+            (Value::Point(vec![181.0, 0.0]), "coordinate_range"),
+            (Value::Point(vec![0.0, f64::NAN]), "coordinate_range"),
+            (Value::MultiPoint(vec![vec![0.0, 0.0], vec![0.0, -91.0]]), "coordinate_range"),
+            (Value::LineString(vec![vec![0.0, 0.0]]), "line_too_short"),
+            (Value::Polygon(vec![]), "ring_invalid"),
+            (Value::Polygon(vec![ring[..3].to_vec()]), "ring_invalid"),
+            (Value::Polygon(vec![open_ring]), "ring_invalid"),
+        ];
+        for (value, code) in invalid {
+            let error = geometry(&Geometry::new(value.clone())).expect_err("invalid geometry");
+            assert_eq!(error.code, code, "{value:?}");
+        }
+
+        let mut with_bbox = Geometry::new(Value::Point(vec![0.0, 0.0]));
+        with_bbox.bbox = Some(vec![10.0, 0.0, -10.0]);
+        assert_eq!(geometry(&with_bbox).expect_err("bbox").code, "bbox");
+    }
+}
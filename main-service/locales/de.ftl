@@ -0,0 +1,102 @@
+validation-required = { $field } ist erforderlich
+validation-invalid = { $field } ist ungültig
+validation-length = { $field } hat eine ungültige Länge
+validation-length-min = { $field } muss mindestens { $min } Zeichen lang sein
+validation-length-max = { $field } darf höchstens { $max } Zeichen lang sein
+validation-length-between = { $field } muss zwischen { $min } und { $max } Zeichen lang sein
+validation-length-equal = { $field } muss genau { $equal } Zeichen lang sein
+validation-range = { $field } liegt außerhalb des erlaubten Bereichs
+validation-range-min = { $field } muss mindestens { $min } sein
+validation-range-max = { $field } darf höchstens { $max } sein
+validation-range-between = { $field } muss zwischen { $min } und { $max } liegen
+validation-email = { $field } ist keine gültige E-Mail-Adresse
+validation-url = { $field } ist keine gültige URL
+validation-regex = { $field } ist ungültig
+validation-must_match = { $field } muss mit { $other } übereinstimmen
+validation-contains = { $field } muss { $needle } enthalten
+validation-does_not_contain = { $field } darf { $needle } nicht enthalten
+validation-credit_card = { $field } ist keine gültige Kartennummer
+validation-non_control_character = { $field } darf keine Steuerzeichen enthalten
+validation-unique = { $field } ist bereits vergeben
+validation-foreign_key = { $field } verweist auf einen Datensatz, der nicht existiert
+validation-password_incorrect = { $field } ist falsch
+validation-password_too_short = { $field } muss mindestens { $min } Zeichen haben
+validation-password_too_long = { $field } darf höchstens { $max } Zeichen haben
+validation-password_too_common = { $field } ist zu verbreitet
+validation-password_matches_username = { $field } darf nicht dem Benutzernamen entsprechen
+validation-image_type = { $field } muss ein PNG-, JPEG-, WebP- oder GIF-Bild sein
+validation-image_undecodable = { $field } konnte nicht gelesen werden
+validation-file_too_large = { $field } darf { $max } Bytes nicht überschreiten
+validation-file_type = { $field } darf nicht vom Typ { $content_type } sein
+validation-file_unexpected = { $field } wird als Datei nicht angenommen
+validation-multipart_fields = { $field } ist ungültig
+validation-offset_with_page = { $field } kann nicht mit page kombiniert werden
+validation-not_sortable = nach { $value } kann nicht sortiert werden
+validation-malformed_filter = Filter ist fehlerhaft
+validation-not_filterable = nach { $value } kann nicht gefiltert werden
+validation-boolean = { $field } erwartet true oder false
+validation-unknown_operator = unbekannter Filteroperator { $value }
+validation-text_operator = { $value } ist nur auf Textfelder anwendbar
+validation-number = { $field } muss eine positive Zahl sein
+validation-type_mismatch = { $value } ist kein gültiger Wert vom Typ { $kind }
+validation-cursor_invalid = Cursor ist ungültig
+validation-cursor_sort_mismatch = Cursor wurde für eine andere Sortierung ausgestellt
+validation-cursor_filter_mismatch = Cursor wurde für andere Filter ausgestellt
+validation-uuid = { $field } ist keine gültige UUID
+validation-phone = { $field } muss eine Telefonnummer im internationalen Format sein, z. B. +4930123456
+validation-country = { $field } ist kein ISO-3166-Ländercode
+validation-locale = { $field } ist kein gültiges Sprachkürzel
+validation-timezone = { $field } ist keine gültige Zeitzone
+validation-hex_color = { $field } muss eine Hex-Farbe wie #1e90ff sein
+validation-bbox = { $field } muss ein Begrenzungsrahmen aus West, Süd, Ost und Nord sein
+validation-coordinate_range = { $field } enthält eine Koordinate außerhalb des gültigen Bereichs bei { $longitude }, { $latitude }
+validation-position_invalid = { $field } enthält eine Position ohne Längen- und Breitengrad
+validation-line_too_short = { $field } enthält eine Linie mit weniger als zwei Positionen
+validation-ring_invalid = { $field } enthält einen Polygonring, der nicht geschlossen ist oder weniger als vier Positionen hat
+
+error-NOT_FOUND = Der angeforderte Datensatz existiert nicht.
+error-BAD_REQUEST = Die Anfrage kann so nicht verarbeitet werden.
+error-AUTH_UNAUTHORIZED = Für diese Ressource ist eine Anmeldung erforderlich.
+error-AUTH_TOKEN_EXPIRED = Die Anmeldung ist abgelaufen, bitte melden Sie sich erneut an.
+error-AUTH_FORBIDDEN = Ihnen fehlt die Berechtigung für diese Anfrage.
+error-INTERNAL_ERROR = Ein unerwarteter Fehler ist aufgetreten.
+error-INTERNAL_ERROR_WITH_CONTEXT = Ein unerwarteter Fehler ist aufgetreten.
+error-CONFLICT = Die Anfrage steht im Konflikt mit dem aktuellen Zustand des Datensatzes.
+error-PRECONDITION_FAILED = Eine Vorbedingung der Anfrage ist nicht erfüllt.
+error-REQUEST_INVALID_JSON = Der Inhalt ist kein gültiges JSON für diesen Endpunkt.
+error-REQUEST_INVALID_QUERY = Die Abfrageparameter können nicht gelesen werden.
+error-REQUEST_INVALID_PATH = Ein Pfadparameter kann nicht gelesen werden.
+error-REQUEST_INVALID_MULTIPART = Der Multipart-Inhalt ist fehlerhaft oder zu groß.
+error-REQUEST_INVALID_BODY = Der MessagePack- oder CBOR-Inhalt kann nicht gelesen werden.
+error-REQUEST_NOT_MULTIPART = Der Inhalt ist kein multipart/form-data.
+error-REQUEST_INVALID_FORM = Der Inhalt ist kein gültiges Formular für diesen Endpunkt.
+error-REQUEST_BODY_TOO_LARGE = Der Inhalt ist größer als vom Server erlaubt.
+error-REQUEST_JSON_TOO_LARGE = Der JSON-Inhalt ist größer als vom Server erlaubt.
+error-VALIDATION_FAILED = Die Anfrage enthält ungültige Felder.
+error-VALIDATION_RULE_FAILED = Eine Prüfregel der Anfrage ist fehlgeschlagen.
+error-VALIDATION_FIELD_INVALID = Die Anfrage enthält ein ungültiges Feld.
+error-VALIDATION_FIELDS_INVALID = Die Anfrage enthält ungültige Felder.
+error-UNPROCESSABLE_ENTITY = Die Anfrage kann nicht verarbeitet werden.
+error-SERIALIZATION_ERROR = Ein unerwarteter Fehler ist aufgetreten.
+error-UNEXPECTED_ERROR = Ein unerwarteter Fehler ist aufgetreten.
+error-IO_ERROR = Ein unerwarteter Fehler ist aufgetreten.
+error-DATABASE_ERROR = Ein unerwarteter Fehler ist aufgetreten.
+error-DATABASE_TRANSACTION_ERROR = Ein unerwarteter Fehler ist aufgetreten.
+error-DATABASE_DRIVER_ERROR = Ein unerwarteter Fehler ist aufgetreten.
+error-DECODE_ERROR = Ein unerwarteter Fehler ist aufgetreten.
+error-PROJECT_PARTICIPANT_QUOTA_EXCEEDED = Das Projekt hat die maximale Anzahl an Teilnehmenden erreicht.
+error-PROJECT_PARTICIPANT_ALREADY_EXISTS = Die Person nimmt bereits an dem Projekt teil.
+error-PROJECT_VERSION_ID_MISMATCH = Die Projektversion entspricht nicht der aktuellen Version.
+error-VARIABLE_PARSE_FAILED = Eine Projektvariable kann nicht gelesen werden.
+error-UUID_INVALID = Ein unerwarteter Fehler ist aufgetreten.
+error-PASSWORD_HASH_ERROR = Ein unerwarteter Fehler ist aufgetreten.
+error-DB_UNIQUE_VIOLATION = Ein Datensatz mit diesem Wert existiert bereits.
+error-DB_FOREIGN_KEY_VIOLATION = Die Änderung steht im Konflikt mit verknüpften Datensätzen.
+error-DB_NOT_NULL_VIOLATION = Ein erforderlicher Wert fehlt.
+error-DB_CHECK_VIOLATION = Ein Wert ist ungültig.
+error-DB_RECORD_NOT_FOUND = Der Datensatz existiert nicht.
+error-DB_UNAVAILABLE = Der Dienst ist vorübergehend nicht verfügbar, bitte später erneut versuchen.
+error-ROUTE_NOT_FOUND = Die angeforderte Ressource existiert auf diesem Server nicht.
+error-REQUEST_TIMEOUT = Die Anfrage hat zu lange gedauert.
+error-SERVICE_ERROR = Ein unerwarteter Fehler ist aufgetreten.
+error-HTTP_ERROR = Die Anfrage ist fehlgeschlagen.
@@ -0,0 +1,71 @@
+## Field messages, looked up as `validation-{code}`. `$field` names the field, the other
+## variables are the parameters of the failed rule. Bounded rules may add `-min`, `-max`,
+## `-between` and `-equal` variants.
+##
+## Error responses use `error-{code}` with the codes of `ErrorCode`. English messages are
+## written in code, so only other locales define them.
+
+validation-required = { $field } is required
+validation-invalid = { $field } is invalid
+validation-length = { $field } has an invalid length
+validation-length-min = { $field } must be at least { $min ->
+    [one] { $min } character
+   *[other] { $min } characters
+} long
+validation-length-max = { $field } must be at most { $max ->
+    [one] { $max } character
+   *[other] { $max } characters
+} long
+validation-length-between = { $field } must be between { $min } and { $max } characters long
+validation-length-equal = { $field } must be exactly { $equal ->
+    [one] { $equal } character
+   *[other] { $equal } characters
+} long
+validation-range = { $field } is out of range
+validation-range-min = { $field } must be at least { $min }
+validation-range-max = { $field } must be at most { $max }
+validation-range-between = { $field } must be between { $min } and { $max }
+validation-email = { $field } is not a valid email address
+validation-url = { $field } is not a valid URL
+validation-regex = { $field } is invalid
+validation-must_match = { $field } must match { $other }
+validation-contains = { $field } must contain { $needle }
+validation-does_not_contain = { $field } must not contain { $needle }
+validation-credit_card = { $field } is not a valid card number
+validation-non_control_character = { $field } must not contain control characters
+validation-unique = { $field } already exists
+validation-foreign_key = { $field } references a record that does not exist
+validation-password_incorrect = { $field } is incorrect
+validation-password_too_short = { $field } must be at least { $min } characters
+validation-password_too_long = { $field } must be at most { $max } characters
+validation-password_too_common = { $field } is too common
+validation-password_matches_username = { $field } must not match the username
+validation-image_type = { $field } must be a PNG, JPEG, WebP or GIF image
+validation-image_undecodable = { $field } could not be decoded
+validation-file_too_large = { $field } must not exceed { $max } bytes
+validation-file_type = { $field } must not be of type { $content_type }
+validation-file_unexpected = { $field } is not accepted as a file
+validation-multipart_fields = { $field } is invalid
+validation-offset_with_page = { $field } cannot be combined with page
+validation-not_sortable = { $value } is not sortable
+validation-malformed_filter = malformed filter
+validation-not_filterable = { $value } is not filterable
+validation-boolean = { $field } expects true or false
+validation-unknown_operator = unknown filter operator { $value }
+validation-text_operator = { $value } only applies to text fields
+validation-number = { $field } must be a positive number
+validation-type_mismatch = { $value } is not a valid { $kind }
+validation-cursor_invalid = cursor is invalid
+validation-cursor_sort_mismatch = cursor was issued for a different sort
+validation-cursor_filter_mismatch = cursor was issued for different filters
+validation-uuid = { $field } is not a valid UUID
+validation-phone = { $field } must be a phone number in international format, e.g. +4930123456
+validation-country = { $field } is not an ISO 3166 country code
+validation-locale = { $field } is not a valid language tag
+validation-timezone = { $field } is not a valid time zone
+validation-hex_color = { $field } must be a hex color such as #1e90ff
+validation-bbox = { $field } must be a bounding box of west, south, east and north
+validation-coordinate_range = { $field } has a coordinate out of range at { $longitude }, { $latitude }
+validation-position_invalid = { $field } has a position without longitude and latitude
+validation-line_too_short = { $field } has a line with fewer than two positions
+validation-ring_invalid = { $field } has a polygon ring that is not closed or has fewer than four positions
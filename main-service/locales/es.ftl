@@ -0,0 +1,111 @@
+validation-required = { $field } es obligatorio
+validation-invalid = { $field } no es válido
+validation-length = { $field } tiene una longitud no válida
+validation-length-min = { $field } debe tener al menos { $min ->
+    [one] { $min } carácter
+   *[other] { $min } caracteres
+}
+validation-length-max = { $field } debe tener como máximo { $max ->
+    [one] { $max } carácter
+   *[other] { $max } caracteres
+}
+validation-length-between = { $field } debe tener entre { $min } y { $max } caracteres
+validation-length-equal = { $field } debe tener exactamente { $equal ->
+    [one] { $equal } carácter
+   *[other] { $equal } caracteres
+}
+validation-range = { $field } está fuera del rango permitido
+validation-range-min = { $field } debe ser al menos { $min }
+validation-range-max = { $field } debe ser como máximo { $max }
+validation-range-between = { $field } debe estar entre { $min } y { $max }
+validation-email = { $field } no es un correo electrónico válido
+validation-url = { $field } no es una URL válida
+validation-regex = { $field } no es válido
+validation-must_match = { $field } debe coincidir con { $other }
+validation-contains = { $field } debe contener { $needle }
+validation-does_not_contain = { $field } no debe contener { $needle }
+validation-credit_card = { $field } no es un número de tarjeta válido
+validation-non_control_character = { $field } no debe contener caracteres de control
+validation-unique = { $field } ya existe
+validation-foreign_key = { $field } hace referencia a un registro que no existe
+validation-password_incorrect = { $field } es incorrecta
+validation-password_too_short = { $field } debe tener al menos { $min } caracteres
+validation-password_too_long = { $field } debe tener como máximo { $max } caracteres
+validation-password_too_common = { $field } es demasiado común
+validation-password_matches_username = { $field } no debe coincidir con el nombre de usuario
+validation-image_type = { $field } debe ser una imagen PNG, JPEG, WebP o GIF
+validation-image_undecodable = { $field } no se pudo leer
+validation-file_too_large = { $field } no debe superar { $max } bytes
+validation-file_type = { $field } no puede ser de tipo { $content_type }
+validation-file_unexpected = { $field } no se acepta como archivo
+validation-multipart_fields = { $field } no es válido
+validation-offset_with_page = { $field } no se puede combinar con page
+validation-not_sortable = no se puede ordenar por { $value }
+validation-malformed_filter = filtro mal formado
+validation-not_filterable = no se puede filtrar por { $value }
+validation-boolean = { $field } espera true o false
+validation-unknown_operator = operador de filtro desconocido { $value }
+validation-text_operator = { $value } solo se aplica a campos de texto
+validation-number = { $field } debe ser un número positivo
+validation-type_mismatch = { $value } no es un valor { $kind } válido
+validation-cursor_invalid = el cursor no es válido
+validation-cursor_sort_mismatch = el cursor se emitió para otro orden
+validation-cursor_filter_mismatch = el cursor se emitió para otros filtros
+validation-uuid = { $field } no es un UUID válido
+validation-phone = { $field } debe ser un número de teléfono en formato internacional, p. ej. +4930123456
+validation-country = { $field } no es un código de país ISO 3166
+validation-locale = { $field } no es una etiqueta de idioma válida
+validation-timezone = { $field } no es una zona horaria válida
+validation-hex_color = { $field } debe ser un color hexadecimal como #1e90ff
+validation-bbox = { $field } debe ser un cuadro delimitador de oeste, sur, este y norte
+validation-coordinate_range = { $field } tiene una coordenada fuera de rango en { $longitude }, { $latitude }
+validation-position_invalid = { $field } tiene una posición sin longitud y latitud
+validation-line_too_short = { $field } tiene una línea con menos de dos posiciones
+validation-ring_invalid = { $field } tiene un anillo de polígono sin cerrar o con menos de cuatro posiciones
+
+error-NOT_FOUND = El registro solicitado no existe.
+error-BAD_REQUEST = La solicitud no se puede procesar tal como se envió.
+error-AUTH_UNAUTHORIZED = Se requiere autenticación para acceder a este recurso.
+error-AUTH_TOKEN_EXPIRED = La sesión ha caducado, vuelva a iniciar sesión.
+error-AUTH_FORBIDDEN = No tiene permiso para realizar esta solicitud.
+error-INTERNAL_ERROR = Se ha producido un error inesperado.
+error-INTERNAL_ERROR_WITH_CONTEXT = Se ha producido un error inesperado.
+error-CONFLICT = La solicitud entra en conflicto con el estado actual del registro.
+error-PRECONDITION_FAILED = No se cumple una condición previa de la solicitud.
+error-REQUEST_INVALID_JSON = El contenido no es un JSON válido para este endpoint.
+error-REQUEST_INVALID_QUERY = Los parámetros de consulta no se pueden leer.
+error-REQUEST_INVALID_PATH = Un parámetro de la ruta no se puede leer.
+error-REQUEST_INVALID_MULTIPART = El contenido multipart está mal formado o es demasiado grande.
+error-REQUEST_INVALID_BODY = El contenido MessagePack o CBOR no se puede leer.
+error-REQUEST_NOT_MULTIPART = El contenido no es multipart/form-data.
+error-REQUEST_INVALID_FORM = El contenido no es un formulario válido para este endpoint.
+error-REQUEST_BODY_TOO_LARGE = El contenido supera el tamaño permitido por el servidor.
+error-REQUEST_JSON_TOO_LARGE = El contenido JSON supera el tamaño permitido por el servidor.
+error-VALIDATION_FAILED = La solicitud contiene campos no válidos.
+error-VALIDATION_RULE_FAILED = Una regla de validación de la solicitud ha fallado.
+error-VALIDATION_FIELD_INVALID = La solicitud contiene un campo no válido.
+error-VALIDATION_FIELDS_INVALID = La solicitud contiene campos no válidos.
+error-UNPROCESSABLE_ENTITY = La solicitud no se puede procesar.
+error-SERIALIZATION_ERROR = Se ha producido un error inesperado.
+error-UNEXPECTED_ERROR = Se ha producido un error inesperado.
+error-IO_ERROR = Se ha producido un error inesperado.
+error-DATABASE_ERROR = Se ha producido un error inesperado.
+error-DATABASE_TRANSACTION_ERROR = Se ha producido un error inesperado.
+error-DATABASE_DRIVER_ERROR = Se ha producido un error inesperado.
+error-DECODE_ERROR = Se ha producido un error inesperado.
+error-PROJECT_PARTICIPANT_QUOTA_EXCEEDED = El proyecto ha alcanzado su límite de participantes.
+error-PROJECT_PARTICIPANT_ALREADY_EXISTS = La persona ya participa en el proyecto.
+error-PROJECT_VERSION_ID_MISMATCH = La versión del proyecto no coincide con la más reciente.
+error-VARIABLE_PARSE_FAILED = No se puede leer una variable del proyecto.
+error-UUID_INVALID = Se ha producido un error inesperado.
+error-PASSWORD_HASH_ERROR = Se ha producido un error inesperado.
+error-DB_UNIQUE_VIOLATION = Ya existe un registro con este valor.
+error-DB_FOREIGN_KEY_VIOLATION = El cambio entra en conflicto con registros relacionados.
+error-DB_NOT_NULL_VIOLATION = Falta un valor obligatorio.
+error-DB_CHECK_VIOLATION = Un valor no es válido.
+error-DB_RECORD_NOT_FOUND = El registro no existe.
+error-DB_UNAVAILABLE = El servicio no está disponible temporalmente, inténtelo de nuevo.
+error-ROUTE_NOT_FOUND = El recurso solicitado no existe en este servidor.
+error-REQUEST_TIMEOUT = La solicitud tardó demasiado.
+error-SERVICE_ERROR = Se ha producido un error inesperado.
+error-HTTP_ERROR = La solicitud ha fallado.
@@ -0,0 +1,38 @@
+use crate::infrastructure::config::CargoEnv;
+use clap::{Parser, Subcommand};
+
+#[derive(Parser, Debug)]
+#[command(version, about)]
+pub struct AppRunCli {
+    /// TOML config file. Optional when every required key is set through `APP_*` variables.
+    #[arg(long, env = "APP_CONFIG", global = true)]
+    pub config: Option<String>,
+    #[arg(long, value_enum, default_value = "development", global = true)]
+    pub cargo_env: CargoEnv,
+    /// Override a config key, e.g. `--set port=9000`. Wins over the file and the environment.
+    #[arg(long = "set", value_name = "KEY=VALUE", value_parser = parse_override, global = true)]
+    pub overrides: Vec<(String, String)>,
+    /// Runs the server when omitted.
+    #[command(subcommand)]
+    pub command: Option<AppCommand>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum AppCommand {
+    /// Inspect the configuration.
+    #[command(subcommand)]
+    Config(ConfigCommand),
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommand {
+    /// Validate the configuration and print the effective values with secrets masked.
+    Check,
+}
+
+fn parse_override(arg: &str) -> Result<(String, String), String> {
+    match arg.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => Ok((key.trim().to_string(), value.to_string())),
+        _ => Err(format!("expected KEY=VALUE, got `{arg}`")),
+    }
+}
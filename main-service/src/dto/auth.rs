@@ -17,6 +17,37 @@ pub struct Claims {
     pub exp: usize,  // Expiry time of the token
     pub iat: usize,  // Issued at time of the token
     pub sub: String,  // user id
+    pub sid: String,  // session id
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,  // restricted scope, `None` means full access
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub act: Option<ActorClaims>,  // admin acting as `sub` while impersonating
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ActorClaims {
+    pub sub: String,  // admin user id
+    pub sid: String,  // impersonation audit id
+}
+
+impl Claims {
+    pub fn new(sub: String, sid: String, expires_in: TimeDelta) -> Self {
+        let now = chrono::Utc::now();
+        Self {
+            exp: (now + expires_in).timestamp() as usize,
+            iat: now.timestamp() as usize,
+            sub,
+            sid,
+            scope: None,
+            act: None,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, Validate, Default)]
+pub struct ImpersonationPayload {
+    #[validate(length(max = 500))]
+    pub reason: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -24,6 +55,8 @@ pub struct OAuth2Response {
     pub token_type: String,
     pub access_token: String,
     pub expires_in: TimeDelta,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
 }
 
 impl OAuth2Response {
@@ -31,7 +64,23 @@ impl OAuth2Response {
         Self {
             token_type: String::from("Bearer"),
             access_token: token,
-            expires_in
+            expires_in,
+            scope: None,
         }
     }
 }
+
+pub const DEVICE_SCOPE: &str = "device";
+
+#[derive(Serialize)]
+pub struct DevicePairingResponse {
+    pub code: String,
+    pub qr_code: String,  // PNG data URL of `code`
+    pub expires_in: u64,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, Validate, Default)]
+pub struct DeviceTokenPayload {
+    #[validate(required, length(min = 1, max = 16))]
+    pub code: Option<String>,
+}
@@ -6,12 +6,62 @@ pub struct BaseResponse<T = ()> {
     pub data: Option<T>, // Data payload for success
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<ErrorDetails>, // Error details for failures
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meta: Option<ResponseMeta>, // Page metadata for list responses
 }
 
 #[derive(Serialize)]
 pub struct ErrorDetails {
     pub message: String, // Error message
-    pub code: Option<u16>, // Optional error code
+    pub code: String, // Stable error code, see `ErrorCode`
+    pub status: Option<u16>, // HTTP status
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>, // Id of the logged cause of server errors
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum ResponseMeta {
+    Page(PageMeta),
+    Cursor(CursorMeta),
+}
+
+#[derive(Serialize)]
+pub struct CursorMeta {
+    pub limit: u64,
+    pub next: Option<String>,
+    pub prev: Option<String>,
+}
+
+impl From<CursorMeta> for ResponseMeta {
+    fn from(meta: CursorMeta) -> Self {
+        ResponseMeta::Cursor(meta)
+    }
+}
+
+impl From<PageMeta> for ResponseMeta {
+    fn from(meta: PageMeta) -> Self {
+        ResponseMeta::Page(meta)
+    }
+}
+
+#[derive(Serialize)]
+pub struct PageMeta {
+    pub total: u64,
+    pub page: u64,
+    pub per_page: u64,
+    pub total_pages: u64,
+}
+
+impl PageMeta {
+    pub fn new(total: u64, page: u64, per_page: u64) -> Self {
+        PageMeta {
+            total,
+            page,
+            per_page,
+            total_pages: total.div_ceil(per_page.max(1)),
+        }
+    }
 }
 
 impl<T> BaseResponse<T> {
@@ -19,13 +69,35 @@ impl<T> BaseResponse<T> {
         BaseResponse {
             data: Some(data),
             error: None,
+            meta: None,
         }
     }
 
-    pub fn error(message: String, code: Option<u16>) -> Self {
+    pub fn paginated(data: T, meta: impl Into<ResponseMeta>) -> Self {
+        BaseResponse {
+            data: Some(data),
+            error: None,
+            meta: Some(meta.into()),
+        }
+    }
+
+    pub fn error(message: String, code: &str, status: Option<u16>) -> Self {
         BaseResponse {
             data: None,
-            error: Some(ErrorDetails { message, code }),
+            error: Some(ErrorDetails {
+                message,
+                code: code.to_string(),
+                status,
+                correlation_id: None,
+            }),
+            meta: None,
+        }
+    }
+
+    pub fn with_correlation_id(mut self, correlation_id: String) -> Self {
+        if let Some(error) = self.error.as_mut() {
+            error.correlation_id = Some(correlation_id);
         }
+        self
     }
 }
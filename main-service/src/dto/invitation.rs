@@ -0,0 +1,118 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+use entity::user_invitation;
+use crate::dto::permission::Permission;
+use crate::infrastructure::errors::AppResult;
+use crate::infrastructure::validators;
+
+#[derive(Clone, Serialize, Deserialize, Debug, Validate, Default)]
+pub struct InvitationNewDto {
+    #[validate(required, length(min = 1), email(message = "email is invalid"))]
+    pub email: Option<String>,
+    /// Permissions granted to the account once the invitation is accepted.
+    pub permissions: Option<Vec<Permission>>,
+    #[validate(custom(function = validators::uuid))]
+    pub project_id: Option<String>,
+}
+
+impl InvitationNewDto {
+    /// `project_id` once validated.
+    pub fn project_id(&self) -> Option<Uuid> {
+        self.project_id.as_deref().and_then(|id| Uuid::parse_str(id).ok())
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, Validate, Default)]
+pub struct AcceptInvitationDto {
+    #[validate(required, length(min = 1))]
+    pub token: Option<String>,
+    /// New password, or the current one when the email already has an account.
+    #[validate(required, length(min = 1))]
+    pub password: Option<String>,
+    #[validate(length(min = 1, max = 100))]
+    pub display_name: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum InvitationStatus {
+    #[default]
+    Pending,
+    Accepted,
+    Revoked,
+    Expired,
+    All,
+}
+
+impl InvitationStatus {
+    pub fn of(model: &user_invitation::Model) -> Self {
+        if model.accepted_at.is_some() {
+            InvitationStatus::Accepted
+        } else if model.revoked_at.is_some() {
+            InvitationStatus::Revoked
+        } else if model.expires_at <= Utc::now() {
+            InvitationStatus::Expired
+        } else {
+            InvitationStatus::Pending
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, Validate, Default)]
+pub struct InvitationListParams {
+    pub status: Option<InvitationStatus>,
+}
+
+#[derive(Clone, Serialize, Debug)]
+pub struct InvitationReadResponse {
+    pub id: Uuid,
+    pub email: String,
+    pub permissions: Vec<Permission>,
+    pub project_id: Option<Uuid>,
+    pub status: InvitationStatus,
+    pub invited_by: Uuid,
+    /// Links issued so far, delivering them is up to the inviter.
+    pub sent_count: i32,
+    pub last_sent_at: chrono::DateTime<chrono::FixedOffset>,
+    pub created_at: chrono::DateTime<chrono::FixedOffset>,
+    pub expires_at: chrono::DateTime<chrono::FixedOffset>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accepted_at: Option<chrono::DateTime<chrono::FixedOffset>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accepted_user_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revoked_at: Option<chrono::DateTime<chrono::FixedOffset>>,
+    /// Only present right after the token was issued, it cannot be recovered later.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invite_url: Option<String>,
+}
+
+impl InvitationReadResponse {
+    pub fn from_model(model: user_invitation::Model) -> AppResult<Self> {
+        Ok(
+            InvitationReadResponse {
+                permissions: Permission::from_json(&model.permissions),
+                status: InvitationStatus::of(&model),
+                id: model.id,
+                email: model.email,
+                project_id: model.project_id,
+                invited_by: model.invited_by,
+                sent_count: model.sent_count,
+                last_sent_at: model.last_sent_at,
+                created_at: model.created_at,
+                expires_at: model.expires_at,
+                accepted_at: model.accepted_at,
+                accepted_user_id: model.accepted_user_id,
+                revoked_at: model.revoked_at,
+                invite_url: None,
+            }
+        )
+    }
+
+    pub fn with_invite_url(mut self, invite_url: String) -> Self {
+        self.invite_url = Some(invite_url);
+        self
+    }
+}
@@ -1,4 +1,8 @@
 pub mod user;
 pub mod auth;
 pub mod project;
-pub mod base;
\ No newline at end of file
+pub mod base;
+pub mod permission;
+pub mod session;
+pub mod privacy;
+pub mod invitation;
\ No newline at end of file
@@ -0,0 +1,44 @@
+use entity::user_account;
+use serde::{Deserialize, Serialize};
+
+use crate::infrastructure::errors::{AppError, AppResult};
+
+/// Permissions stored as a JSON array of strings in `user_account.permissions`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Permission {
+    #[serde(rename = "user:manage")]
+    UserManage,
+    #[serde(rename = "user:impersonate")]
+    UserImpersonate,
+}
+
+impl Permission {
+    pub fn of(user: &user_account::Model) -> Vec<Permission> {
+        Self::from_json(&user.permissions)
+    }
+
+    /// Parse a stored JSON array, ignoring permissions this build no longer knows.
+    pub fn from_json(permissions: &serde_json::Value) -> Vec<Permission> {
+        permissions
+            .as_array()
+            .map(|values| {
+                values
+                    .iter()
+                    .filter_map(|value| serde_json::from_value(value.clone()).ok())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn granted_to(self, user: &user_account::Model) -> bool {
+        Self::of(user).contains(&self)
+    }
+
+    pub fn ensure(self, user: &user_account::Model) -> AppResult<()> {
+        if self.granted_to(user) {
+            Ok(())
+        } else {
+            Err(AppError::Forbidden("Missing required permission".to_string()))
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use uuid::Uuid;
+use validator::Validate;
+use entity::data_export;
+use crate::infrastructure::errors::{AppError, AppResult};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportStatus {
+    Pending,
+    Running,
+    Completed,
+    Failed,
+}
+
+impl ExportStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExportStatus::Pending => "pending",
+            ExportStatus::Running => "running",
+            ExportStatus::Completed => "completed",
+            ExportStatus::Failed => "failed",
+        }
+    }
+
+    pub fn parse(value: &str) -> AppResult<Self> {
+        match value {
+            "pending" => Ok(ExportStatus::Pending),
+            "running" => Ok(ExportStatus::Running),
+            "completed" => Ok(ExportStatus::Completed),
+            "failed" => Ok(ExportStatus::Failed),
+            _ => Err(AppError::InternalServerErrorWithContext(format!(
+                "unknown export status {value}"
+            ))),
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Debug)]
+pub struct DataExportResponse {
+    pub id: Uuid,
+    pub status: ExportStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub created_at: chrono::DateTime<chrono::FixedOffset>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<chrono::DateTime<chrono::FixedOffset>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<chrono::DateTime<chrono::FixedOffset>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub download_url: Option<String>,
+}
+
+impl DataExportResponse {
+    pub fn from_model(model: data_export::Model) -> AppResult<Self> {
+        let status = ExportStatus::parse(&model.status)?;
+        Ok(
+            DataExportResponse {
+                download_url: (status == ExportStatus::Completed)
+                    .then(|| format!("/api/users/me/exports/{}/download", model.id)),
+                id: model.id,
+                status,
+                error: model.error,
+                created_at: model.created_at,
+                completed_at: model.completed_at,
+                expires_at: model.expires_at,
+            }
+        )
+    }
+}
+
+/// Self-service erasure, confirmed with the account password.
+#[derive(Clone, Serialize, Deserialize, Debug, Validate, Default)]
+pub struct EraseAccountDto {
+    #[validate(required, length(min = 1))]
+    pub password: Option<String>,
+    /// Also delete projects, submissions and images instead of keeping them pseudonymized.
+    pub purge_content: Option<bool>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, Validate, Default)]
+pub struct EraseUserDto {
+    pub purge_content: Option<bool>,
+}
+
+/// Everything stored about a user, as written into the export archive.
+#[derive(Clone, Serialize, Debug)]
+pub struct DataExportArchive {
+    pub format_version: u32,
+    pub generated_at: chrono::DateTime<chrono::FixedOffset>,
+    pub profile: JsonValue,
+    pub sessions: Vec<JsonValue>,
+    pub impersonations: Vec<JsonValue>,
+    pub projects: Vec<JsonValue>,
+    pub project_participants: Vec<JsonValue>,
+    pub project_data: Vec<JsonValue>,
+    /// Image metadata only, see `excluded`.
+    pub project_data_images: Vec<JsonValue>,
+    pub files: Vec<ExportedFile>,
+    /// Data the archive refers to but does not contain, in plain words.
+    pub excluded: Vec<String>,
+}
+
+/// Uploaded file embedded in the archive as base64.
+#[derive(Clone, Serialize, Debug)]
+pub struct ExportedFile {
+    pub key: String,
+    pub content_type: String,
+    pub data: String,
+}
@@ -0,0 +1,31 @@
+use serde::Serialize;
+use uuid::Uuid;
+use entity::user_session;
+use crate::infrastructure::errors::AppResult;
+
+#[derive(Clone, Serialize, Debug)]
+pub struct SessionReadResponse {
+    pub id: Uuid,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: chrono::DateTime<chrono::FixedOffset>,
+    pub last_seen_at: chrono::DateTime<chrono::FixedOffset>,
+    pub expires_at: chrono::DateTime<chrono::FixedOffset>,
+    pub current: bool,
+}
+
+impl SessionReadResponse {
+    pub fn from_model(model: user_session::Model, current_session_id: &Uuid) -> AppResult<Self> {
+        Ok(
+            SessionReadResponse {
+                current: model.id == *current_session_id,
+                id: model.id,
+                user_agent: model.user_agent,
+                ip_address: model.ip_address,
+                created_at: model.created_at,
+                last_seen_at: model.last_seen_at,
+                expires_at: model.expires_at,
+            }
+        )
+    }
+}
@@ -1,33 +1,234 @@
+use geojson::Geometry;
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 use validator::Validate;
 use entity::user_account;
-use crate::infrastructure::errors::AppResult;
+use crate::dto::permission::Permission;
+use crate::extractor::async_validator::{AsyncValidate, ValidationContext};
+use crate::extractor::validator::{FileLimit, MultipartForm};
+use crate::infrastructure::config::Config;
+use crate::infrastructure::errors::{AppResult, ValidationMessageError};
+use crate::infrastructure::validation_code::ValidationCode;
+use crate::infrastructure::validators;
+use crate::repository::user::UserRepository;
+use crate::infrastructure::storage::Storage;
+use crate::service::avatar::{AvatarService, AVATAR_CONTENT_TYPES, AVATAR_SIZES};
 
 #[derive(Clone, Serialize, Deserialize, Debug, Validate, Default)]
 pub struct UserNewDto {
-    // #[validate(required, length(min = 1))]
-    // pub name: Option<String>,
+    #[validate(length(min = 1, max = 100))]
+    pub display_name: Option<String>,
     #[validate(required, length(min = 1), email(message = "email is invalid"))]
     pub username: Option<String>,
-    #[validate(required, length(min = 6))]
+    #[validate(required, length(min = 6), custom(function = validators::strong_password))]
     pub password: Option<String>,
 }
 
+#[derive(Clone, Serialize, Deserialize, Debug, Validate, Default)]
+pub struct UserUpdateDto {
+    #[validate(length(min = 1), email(message = "email is invalid"))]
+    pub username: Option<String>,
+    #[validate(length(min = 1, max = 100))]
+    pub display_name: Option<String>,
+    #[validate(custom(function = validators::locale))]
+    pub locale: Option<String>,
+    #[validate(custom(function = validators::timezone))]
+    pub timezone: Option<String>,
+    #[validate(custom(function = validators::phone))]
+    pub phone: Option<String>,
+    #[validate(custom(function = validators::hex_color))]
+    pub accent_color: Option<String>,
+    /// Initial extent of maps, a GeoJSON bounding box.
+    #[validate(custom(function = validators::bbox))]
+    pub map_extent: Option<Vec<f64>>,
+    #[validate(custom(function = validators::geometry))]
+    pub location: Option<Geometry>,
+}
+
+impl AsyncValidate for UserNewDto {
+    async fn validate_async(&self, context: &ValidationContext<'_>) -> AppResult<Vec<ValidationMessageError>> {
+        username_available(context, self.username.as_deref(), None).await
+    }
+}
+
+impl AsyncValidate for UserUpdateDto {
+    async fn validate_async(&self, context: &ValidationContext<'_>) -> AppResult<Vec<ValidationMessageError>> {
+        // Keeping their own username is not a conflict
+        let except = context.current_user.map(|user| &user.id);
+        username_available(context, self.username.as_deref(), except).await
+    }
+}
+
+async fn username_available(
+    context: &ValidationContext<'_>,
+    username: Option<&str>,
+    except: Option<&Uuid>,
+) -> AppResult<Vec<ValidationMessageError>> {
+    let Some(username) = username else {
+        return Ok(Vec::new());
+    };
+    // Early feedback only, a concurrent insert still ends up as the same error from the index
+    let taken = UserRepository::new(context.state.db.clone(), context.state.user_cache.clone())
+        .is_username_taken(username, except)
+        .await?;
+    Ok(match taken {
+        true => {
+            let message = "username already exists";
+            vec![ValidationMessageError::new("username", ValidationCode::UNIQUE, message)]
+        }
+        false => Vec::new(),
+    })
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, Validate, Default)]
+pub struct ChangePasswordDto {
+    #[validate(required, length(min = 1))]
+    pub current_password: Option<String>,
+    #[validate(required, length(min = 6), custom(function = validators::strong_password))]
+    pub new_password: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, Validate, Default)]
+pub struct UserPermissionsDto {
+    #[validate(required)]
+    pub permissions: Option<Vec<Permission>>,
+}
+
 #[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct UserReadResponse {
-    // #[validate(required, length(min = 1))]
-    // pub name: Option<String>,
+    pub id: Uuid,
+    pub username: String,
+    pub display_name: Option<String>,
+    pub locale: Option<String>,
+    pub timezone: Option<String>,
+    pub phone: Option<String>,
+    pub accent_color: Option<String>,
+    pub map_extent: Option<serde_json::Value>,
+    pub location: Option<serde_json::Value>,
+    pub avatar: Option<AvatarUrls>,
+    pub permissions: Vec<Permission>,
+    pub created_at: chrono::DateTime<chrono::FixedOffset>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disabled_at: Option<chrono::DateTime<chrono::FixedOffset>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<chrono::DateTime<chrono::FixedOffset>>,
+}
+
+/// One row of the user export. Unlike `UserReadResponse` every column is always present, so
+/// CSV exports get the same columns for every row.
+#[derive(Clone, Serialize, Debug)]
+pub struct UserExportRow {
+    pub id: Uuid,
     pub username: String,
+    pub display_name: Option<String>,
+    pub locale: Option<String>,
+    pub timezone: Option<String>,
+    pub permissions: Vec<Permission>,
     pub created_at: chrono::DateTime<chrono::FixedOffset>,
+    pub disabled_at: Option<chrono::DateTime<chrono::FixedOffset>>,
+    pub deleted_at: Option<chrono::DateTime<chrono::FixedOffset>>,
+}
+
+impl UserExportRow {
+    pub fn from_model(model: user_account::Model) -> Self {
+        UserExportRow {
+            permissions: Permission::of(&model),
+            id: model.id,
+            username: model.username,
+            display_name: model.display_name,
+            locale: model.locale,
+            timezone: model.timezone,
+            created_at: model.created_at,
+            disabled_at: model.disabled_at,
+            deleted_at: model.deleted_at,
+        }
+    }
+}
+
+/// Public URLs of the avatar renditions keyed by size name.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct AvatarUrls {
+    pub small: String,
+    pub medium: String,
+    pub large: String,
+}
+
+impl AvatarUrls {
+    pub fn new(key: &str, storage: &dyn Storage) -> Self {
+        let [small, medium, large] =
+            AVATAR_SIZES.map(|(name, _)| storage.url(&AvatarService::file_key(key, name)));
+        AvatarUrls { small, medium, large }
+    }
 }
 
 impl UserReadResponse {
-    pub fn from_model(model: user_account::Model) -> AppResult<Self> {
+    pub fn from_model(model: user_account::Model, storage: &dyn Storage) -> AppResult<Self> {
         Ok(
             UserReadResponse {
+                permissions: Permission::of(&model),
+                avatar: model.avatar_key.as_deref().map(|key| AvatarUrls::new(key, storage)),
+                id: model.id,
                 username: model.username,
+                display_name: model.display_name,
+                locale: model.locale,
+                timezone: model.timezone,
+                phone: model.phone,
+                accent_color: model.accent_color,
+                map_extent: model.map_extent,
+                location: model.location,
                 created_at: model.created_at,
+                disabled_at: model.disabled_at,
+                deleted_at: model.deleted_at,
             }
         )
     }
-}
\ No newline at end of file
+}
+/// `multipart/form-data` body of an avatar upload, the image is the `avatar` file part.
+#[derive(Clone, Deserialize, Debug, Validate, Default)]
+pub struct AvatarUploadForm {}
+
+impl MultipartForm for AvatarUploadForm {
+    fn file_limit(name: &str, config: &Config) -> Option<FileLimit> {
+        (name == "avatar").then_some(FileLimit {
+            max_size: config.avatar_max_size,
+            content_types: &AVATAR_CONTENT_TYPES,
+        })
+    }
+
+    fn required_files() -> &'static [&'static str] {
+        &["avatar"]
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::UserUpdateDto;
+    use crate::extractor::async_validator::{AsyncValidate, ValidationContext};
+    use crate::infrastructure::state::AppState;
+    use crate::infrastructure::validation_code::ValidationCode;
+    use crate::test_support;
+    use entity::user_account;
+    use serde_json::json;
+
+    async fn codes(state: &AppState, username: &str, current_user: &user_account::Model) -> Vec<String> {
+        let dto = UserUpdateDto {
+            username: Some(username.to_string()),
+            ..Default::default()
+        };
+        let context = ValidationContext { state, current_user: Some(current_user) };
+        let errors = dto.validate_async(&context).await.unwrap();
+        errors.into_iter().filter_map(|error| error.code).map(String::from).collect()
+    }
+
+    #[tokio::test]
+    async fn update_keeps_the_callers_own_username() {
+        let state =
+            test_support::sqlite_state(|schema| vec![schema.create_table_from_entity(user_account::Entity)]).await;
+        let alice = test_support::insert_user(&state, "alice@example.com", json!([])).await;
+        test_support::insert_user(&state, "bob@example.com", json!([])).await;
+
+        assert!(codes(&state, "alice@example.com", &alice).await.is_empty());
+        assert!(codes(&state, "carol@example.com", &alice).await.is_empty());
+        assert_eq!(codes(&state, "bob@example.com", &alice).await, [ValidationCode::UNIQUE]);
+    }
+}
@@ -0,0 +1,156 @@
+use crate::infrastructure::errors::{AppError, AppResult, ValidationMessageError, ValidationMessageErrors};
+use crate::infrastructure::state::AppState;
+use axum::extract::{FromRef, FromRequest, Query, Request};
+use axum::{Form, Json};
+use entity::user_account;
+use std::future::Future;
+use validator::Validate;
+
+/// What async rules may look at besides the payload.
+pub struct ValidationContext<'a> {
+    pub state: &'a AppState,
+    /// Set on routes behind `authentication_middleware`.
+    pub current_user: Option<&'a user_account::Model>,
+}
+
+/// Rules `validator` cannot express because they need the database, a cache or the current
+/// user, e.g. "username is already taken". Run by `AsyncValidated` after `Validate`.
+pub trait AsyncValidate {
+    /// Every failed rule of the payload. `Err` is reserved for failures to check a rule.
+    fn validate_async(
+        &self,
+        context: &ValidationContext<'_>,
+    ) -> impl Future<Output = AppResult<Vec<ValidationMessageError>>> + Send;
+}
+
+/// Extractors `AsyncValidated` can wrap.
+pub trait ExtractedValue {
+    type Value;
+
+    fn value(&self) -> &Self::Value;
+}
+
+impl<T> ExtractedValue for Json<T> {
+    type Value = T;
+
+    fn value(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> ExtractedValue for Form<T> {
+    type Value = T;
+
+    fn value(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> ExtractedValue for Query<T> {
+    type Value = T;
+
+    fn value(&self) -> &T {
+        &self.0
+    }
+}
+
+/// Runs both `Validate` and `AsyncValidate` on the value of `E`, e.g.
+/// `AsyncValidated(Json(payload)): AsyncValidated<Json<UserNewDto>>`, and reports the failures
+/// of both in one `ValidationMessageErrors` response.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AsyncValidated<E>(pub E);
+
+impl<E, S> FromRequest<S> for AsyncValidated<E>
+where
+    E: FromRequest<S> + ExtractedValue + Send,
+    E::Value: Validate + AsyncValidate + Sync,
+    AppError: From<E::Rejection>,
+    AppState: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let current_user = req.extensions().get::<user_account::Model>().cloned();
+        let extracted = E::from_request(req, state).await?;
+        let value = extracted.value();
+
+        let mut errors = match value.validate() {
+            Ok(()) => Vec::new(),
+            Err(e) => ValidationMessageErrors::of_validation(&e).errors,
+        };
+        let state = AppState::from_ref(state);
+        let context = ValidationContext {
+            state: &state,
+            current_user: current_user.as_ref(),
+        };
+        errors.extend(value.validate_async(&context).await?);
+        if !errors.is_empty() {
+            return Err(AppError::ValidationMessageErrors(ValidationMessageErrors::from(errors)));
+        }
+        Ok(AsyncValidated(extracted))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AsyncValidate, AsyncValidated, ValidationContext};
+    use crate::infrastructure::errors::{AppError, AppResult, ValidationMessageError};
+    use crate::infrastructure::validation_code::ValidationCode;
+    use crate::test_support;
+    use axum::body::Body;
+    use axum::extract::FromRequest;
+    use axum::http::header::CONTENT_TYPE;
+    use axum::http::Request;
+    use axum::Json;
+    use serde::Deserialize;
+    use validator::Validate;
+
+    #[derive(Deserialize, Validate)]
+    struct Probe {
+        #[validate(length(min = 3))]
+        name: String,
+        tag: String,
+    }
+
+    impl AsyncValidate for Probe {
+        async fn validate_async(&self, _: &ValidationContext<'_>) -> AppResult<Vec<ValidationMessageError>> {
+            Ok(match self.tag == "taken" {
+                true => vec![ValidationMessageError::new("tag", ValidationCode::UNIQUE, "tag is taken")],
+                false => Vec::new(),
+            })
+        }
+    }
+
+    async fn extract(body: &str) -> Result<AsyncValidated<Json<Probe>>, AppError> {
+        let request = Request::post("/")
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        AsyncValidated::from_request(request, &test_support::state()).await
+    }
+
+    #[tokio::test]
+    async fn reports_sync_and_async_failures_together() {
+        let Err(AppError::ValidationMessageErrors(errors)) = extract(r#"{"name":"ab","tag":"taken"}"#).await
+        else {
+            panic!("expected validation errors");
+        };
+        let mut failures: Vec<(&str, Option<&str>)> = errors
+            .errors
+            .iter()
+            .map(|error| (error.field.as_str(), error.code.as_deref()))
+            .collect();
+        failures.sort();
+        assert_eq!(
+            failures,
+            [("name", Some(ValidationCode::LENGTH)), ("tag", Some(ValidationCode::UNIQUE))]
+        );
+    }
+
+    #[tokio::test]
+    async fn passes_valid_payloads_through() {
+        let AsyncValidated(Json(probe)) = extract(r#"{"name":"abc","tag":"free"}"#).await.unwrap();
+        assert_eq!(probe.name, "abc");
+    }
+}
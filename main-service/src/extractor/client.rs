@@ -0,0 +1,131 @@
+use crate::infrastructure::config::Config;
+use axum::extract::{ConnectInfo, FromRef, FromRequestParts};
+use axum::http::header::USER_AGENT;
+use axum::http::request::Parts;
+use axum::http::HeaderMap;
+use std::convert::Infallible;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+
+/// Device information recorded when a session is issued.
+#[derive(Debug, Clone, Default)]
+pub struct ClientInfo {
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+}
+
+impl<S> FromRequestParts<S> for ClientInfo
+where
+    Arc<Config>: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let user_agent = parts
+            .headers
+            .get(USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.chars().take(512).collect());
+
+        let config = Arc::<Config>::from_ref(state);
+        let ip_address = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| client_ip(addr.ip(), &parts.headers, &config.trusted_proxies))
+            .map(|ip| ip.to_string());
+
+        Ok(ClientInfo {
+            user_agent,
+            ip_address,
+        })
+    }
+}
+
+/// The address of the client behind the configured proxies. `X-Forwarded-For` is read right
+/// to left as every proxy appends its peer, and only while the hop is a trusted proxy, anyone
+/// else can put whatever they like into the header.
+fn client_ip(peer: IpAddr, headers: &HeaderMap, trusted_proxies: &[IpAddr]) -> IpAddr {
+    let mut client = peer;
+    let hops = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .collect::<Vec<_>>();
+    for hop in hops.into_iter().rev() {
+        if !trusted_proxies.contains(&client) {
+            break;
+        }
+        match hop.trim().parse::<IpAddr>() {
+            Ok(ip) => client = ip,
+            Err(_) => break,
+        }
+    }
+    client
+}
+
+#[cfg(test)]
+mod tests {
+    use super::client_ip;
+    use axum::http::{HeaderMap, HeaderValue};
+    use std::net::IpAddr;
+
+    fn ip(value: &str) -> IpAddr {
+        value.parse().unwrap()
+    }
+
+    fn forwarded(values: &[&str]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for value in values {
+            headers.append("x-forwarded-for", HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn untrusted_peer_ignores_forwarded_for() {
+        let headers = forwarded(&["203.0.113.7"]);
+        assert_eq!(client_ip(ip("198.51.100.1"), &headers, &[ip("10.0.0.1")]), ip("198.51.100.1"));
+        assert_eq!(client_ip(ip("198.51.100.1"), &headers, &[]), ip("198.51.100.1"));
+    }
+
+    #[test]
+    fn trusted_proxy_chain_is_walked_right_to_left() {
+        let trusted = [ip("10.0.0.1"), ip("10.0.0.2")];
+        let headers = forwarded(&["192.0.2.9, 203.0.113.7, 10.0.0.2"]);
+        // 203.0.113.7 is not a proxy we trust, so whatever it forwarded is not believed
+        assert_eq!(client_ip(ip("10.0.0.1"), &headers, &trusted), ip("203.0.113.7"));
+    }
+
+    #[test]
+    fn repeated_headers_form_one_chain() {
+        let trusted = [ip("10.0.0.1"), ip("10.0.0.2")];
+        let headers = forwarded(&["203.0.113.7", "10.0.0.2"]);
+        assert_eq!(client_ip(ip("10.0.0.1"), &headers, &trusted), ip("203.0.113.7"));
+    }
+
+    #[test]
+    fn all_hops_trusted_yields_the_leftmost_hop() {
+        let trusted = [ip("10.0.0.1"), ip("10.0.0.2"), ip("10.0.0.3")];
+        let headers = forwarded(&["10.0.0.3, 10.0.0.2"]);
+        assert_eq!(client_ip(ip("10.0.0.1"), &headers, &trusted), ip("10.0.0.3"));
+    }
+
+    #[test]
+    fn malformed_or_empty_hops_stop_the_walk() {
+        let trusted = [ip("10.0.0.1"), ip("10.0.0.2")];
+        let peer = ip("10.0.0.1");
+        assert_eq!(client_ip(peer, &forwarded(&["203.0.113.7, not-an-ip"]), &trusted), peer);
+        assert_eq!(client_ip(peer, &forwarded(&["203.0.113.7, "]), &trusted), peer);
+        assert_eq!(client_ip(peer, &forwarded(&["203.0.113.7,,10.0.0.2"]), &trusted), ip("10.0.0.2"));
+        assert_eq!(client_ip(peer, &forwarded(&[""]), &trusted), peer);
+        assert_eq!(client_ip(peer, &HeaderMap::new(), &trusted), peer);
+    }
+
+    #[test]
+    fn ipv6_hops_are_parsed() {
+        let headers = forwarded(&["2001:db8::7"]);
+        assert_eq!(client_ip(ip("::1"), &headers, &[ip("::1")]), ip("2001:db8::7"));
+    }
+}
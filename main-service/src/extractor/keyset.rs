@@ -0,0 +1,400 @@
+use crate::extractor::list_query::{invalid, parse_number, typed_value, ListQuery, ListSpec};
+use crate::infrastructure::config::Config;
+use crate::infrastructure::errors::{AppError, AppResult};
+use crate::infrastructure::validation_code::ValidationCode;
+use axum::extract::{FromRef, FromRequestParts, Query};
+use axum::http::request::Parts;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use sea_orm::sea_query::SimpleExpr;
+use sea_orm::{EntityTrait, Order, Value};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::marker::PhantomData;
+use std::sync::Arc;
+use uuid::Uuid;
+
+const DEFAULT_LIMIT: u64 = 20;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CursorDirection {
+    #[serde(rename = "n")]
+    Next,
+    #[serde(rename = "p")]
+    Prev,
+}
+
+/// Position encoded inside a cursor token.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct CursorPayload {
+    s: String,         // sort the cursor was issued for, e.g. `-created_at`
+    f: String,         // digest of the filters the cursor was issued for
+    k: Option<String>, // last seen sort key, absent when sorting by id only
+    id: Uuid,
+    d: CursorDirection,
+}
+
+/// Signs cursor tokens so clients cannot forge arbitrary positions.
+#[derive(Clone)]
+pub struct CursorCodec {
+    key: Vec<u8>,
+}
+
+impl CursorCodec {
+    /// Derive the signing key from the application secret, so a cursor signature is never
+    /// a valid MAC under the key tokens are signed with.
+    pub fn new(secret: &str) -> Self {
+        let key = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+            .expect("HMAC accepts keys of any length")
+            .chain_update(b"cursor")
+            .finalize()
+            .into_bytes();
+        Self { key: key.to_vec() }
+    }
+
+    fn mac(&self, payload: &str) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.key)
+            .expect("HMAC accepts keys of any length");
+        mac.update(payload.as_bytes());
+        mac
+    }
+
+    fn encode(&self, payload: &CursorPayload) -> AppResult<String> {
+        let payload = URL_SAFE_NO_PAD.encode(serde_json::to_vec(payload)?);
+        let signature = URL_SAFE_NO_PAD.encode(self.mac(&payload).finalize().into_bytes());
+        Ok(format!("{payload}.{signature}"))
+    }
+
+    fn decode(&self, token: &str) -> AppResult<CursorPayload> {
+        let invalid_cursor = || invalid("cursor", ValidationCode::CURSOR_INVALID, "cursor is invalid");
+        let (payload, signature) = token.split_once('.').ok_or_else(invalid_cursor)?;
+        let signature = URL_SAFE_NO_PAD
+            .decode(signature)
+            .map_err(|_| invalid_cursor())?;
+        self.mac(payload)
+            .verify_slice(&signature)
+            .map_err(|_| invalid_cursor())?;
+        let payload = URL_SAFE_NO_PAD.decode(payload).map_err(|_| invalid_cursor())?;
+        serde_json::from_slice(&payload).map_err(|_| invalid_cursor().into())
+    }
+}
+
+pub struct Cursor {
+    pub key: Option<Value>,
+    pub id: Uuid,
+    pub direction: CursorDirection,
+}
+
+/// Keyset pagination parsed from the query string:
+/// `?limit=20&sort=-created_at&cursor=<token>` plus the same `filter[...]` syntax as [`ListQuery`].
+/// Rows are ordered by the sort column with the primary key as tie breaker.
+pub struct KeysetQuery<S: ListSpec> {
+    pub limit: u64,
+    pub sort: Option<(<S::Entity as EntityTrait>::Column, Order)>,
+    pub filters: Vec<SimpleExpr>,
+    pub cursor: Option<Cursor>,
+    sort_name: String,
+    filter_digest: String,
+    codec: CursorCodec,
+    spec: PhantomData<S>,
+}
+
+/// Offset pagination by default, keyset pagination when `cursor` or `paging=keyset` is given.
+pub enum Paging<S: ListSpec> {
+    Offset(ListQuery<S>),
+    Keyset(KeysetQuery<S>),
+}
+
+impl<S, St> FromRequestParts<St> for Paging<S>
+where
+    S: ListSpec,
+    St: Send + Sync,
+    Arc<Config>: FromRef<St>,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &St) -> Result<Self, Self::Rejection> {
+        let Query(pairs) = Query::<Vec<(String, String)>>::try_from_uri(&parts.uri)?;
+        let keyset = pairs
+            .iter()
+            .any(|(key, value)| key == "cursor" || (key == "paging" && value == "keyset"));
+        if keyset {
+            let config = Arc::<Config>::from_ref(state);
+            Ok(Paging::Keyset(KeysetQuery::parse(pairs, CursorCodec::new(&config.jwt_secret))?))
+        } else {
+            Ok(Paging::Offset(ListQuery::parse(pairs)?))
+        }
+    }
+}
+
+impl<S, St> FromRequestParts<St> for KeysetQuery<S>
+where
+    S: ListSpec,
+    St: Send + Sync,
+    Arc<Config>: FromRef<St>,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &St) -> Result<Self, Self::Rejection> {
+        let config = Arc::<Config>::from_ref(state);
+        let Query(pairs) = Query::<Vec<(String, String)>>::try_from_uri(&parts.uri)?;
+        Self::parse(pairs, CursorCodec::new(&config.jwt_secret))
+    }
+}
+
+impl<S: ListSpec> KeysetQuery<S> {
+    pub fn parse(pairs: Vec<(String, String)>, codec: CursorCodec) -> AppResult<Self> {
+        let mut limit = None;
+        let mut sort_name = String::new();
+        let mut cursor = None;
+        let mut filters = Vec::new();
+        let mut filter_pairs = Vec::new();
+
+        for (key, value) in pairs {
+            match key.as_str() {
+                "limit" => limit = Some(parse_number(&key, &value)?),
+                "sort" => sort_name = value.trim().to_string(),
+                "cursor" => cursor = Some(codec.decode(&value)?),
+                _ if key.starts_with("filter[") => {
+                    filters.push(ListQuery::<S>::parse_filter(&key, &value)?);
+                    filter_pairs.push((key, value));
+                }
+                _ => {}
+            }
+        }
+
+        let limit = limit.unwrap_or(DEFAULT_LIMIT);
+        if limit == 0 || limit > S::MAX_PER_PAGE {
+            return Err(invalid(
+                "limit",
+                ValidationCode::RANGE,
+                format!("limit must be between 1 and {}", S::MAX_PER_PAGE),
+            )
+            .with_param("min", 1)
+            .with_param("max", S::MAX_PER_PAGE)
+            .into());
+        }
+
+        let sort = match sort_name.as_str() {
+            "" => None,
+            name => {
+                let (field, order) = match name.strip_prefix('-') {
+                    Some(field) => (field, Order::Desc),
+                    None => (name, Order::Asc),
+                };
+                let column = S::sortable(field)
+                    .ok_or_else(|| {
+                        invalid("sort", ValidationCode::NOT_SORTABLE, format!("{field} is not sortable"))
+                            .with_param("value", field)
+                    })?;
+                Some((column, order))
+            }
+        };
+
+        let filter_digest = filter_digest(filter_pairs);
+        let cursor = match cursor {
+            Some(payload) if payload.s != sort_name => {
+                return Err(invalid(
+                    "cursor",
+                    ValidationCode::CURSOR_SORT_MISMATCH,
+                    "cursor was issued for a different sort",
+                )
+                .into())
+            }
+            Some(payload) if payload.f != filter_digest => {
+                return Err(invalid(
+                    "cursor",
+                    ValidationCode::CURSOR_FILTER_MISMATCH,
+                    "cursor was issued for different filters",
+                )
+                .into())
+            }
+            Some(payload) => {
+                let key = match (&sort, payload.k) {
+                    (Some((column, _)), Some(key)) => Some(typed_value("cursor", *column, &key)?),
+                    (None, None) => None,
+                    _ => {
+                        let error = invalid("cursor", ValidationCode::CURSOR_INVALID, "cursor is invalid");
+                        return Err(error.into());
+                    }
+                };
+                Some(Cursor {
+                    key,
+                    id: payload.id,
+                    direction: payload.d,
+                })
+            }
+            None => None,
+        };
+
+        Ok(KeysetQuery {
+            limit,
+            sort,
+            filters,
+            cursor,
+            sort_name,
+            filter_digest,
+            codec,
+            spec: PhantomData,
+        })
+    }
+
+    /// Sign a cursor pointing at a row with the given sort key and id.
+    pub fn cursor_for(
+        &self,
+        key: Option<String>,
+        id: Uuid,
+        direction: CursorDirection,
+    ) -> AppResult<String> {
+        self.codec.encode(&CursorPayload {
+            s: self.sort_name.clone(),
+            f: self.filter_digest.clone(),
+            k: key,
+            id,
+            d: direction,
+        })
+    }
+}
+
+/// Order independent digest of the `filter[...]` pairs, a cursor only continues the same result set.
+fn filter_digest(mut pairs: Vec<(String, String)>) -> String {
+    pairs.sort();
+    let mut hasher = Sha256::new();
+    for (key, value) in pairs {
+        // Length prefixes keep `a=bc` and `ab=c` apart
+        for part in [key, value] {
+            hasher.update((part.len() as u64).to_be_bytes());
+            hasher.update(part.as_bytes());
+        }
+    }
+    URL_SAFE_NO_PAD.encode(&hasher.finalize()[..16])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CursorCodec, CursorDirection, CursorPayload, KeysetQuery};
+    use crate::infrastructure::errors::AppError;
+    use crate::repository::user::UserListSpec;
+    use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+    use base64::Engine;
+    use sea_orm::Value;
+    use uuid::Uuid;
+
+    fn pairs(query: &[(&str, &str)]) -> Vec<(String, String)> {
+        query
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    fn parse(query: &[(&str, &str)]) -> Result<KeysetQuery<UserListSpec>, AppError> {
+        KeysetQuery::parse(pairs(query), CursorCodec::new("secret"))
+    }
+
+    fn code(result: Result<KeysetQuery<UserListSpec>, AppError>) -> String {
+        match result {
+            Err(AppError::ValidationMessageError(error)) => error.code.unwrap_or_default().to_string(),
+            Err(e) => panic!("unexpected error {e:?}"),
+            Ok(_) => panic!("cursor was accepted"),
+        }
+    }
+
+    fn payload(id: Uuid) -> CursorPayload {
+        CursorPayload {
+            s: "-created_at".to_string(),
+            f: String::new(),
+            k: Some("2024-01-01T00:00:00+00:00".to_string()),
+            id,
+            d: CursorDirection::Next,
+        }
+    }
+
+    #[test]
+    fn codec_round_trips() {
+        let codec = CursorCodec::new("secret");
+        let id = Uuid::new_v4();
+        let decoded = codec.decode(&codec.encode(&payload(id)).unwrap()).unwrap();
+        assert_eq!(decoded.s, "-created_at");
+        assert_eq!(decoded.k.as_deref(), Some("2024-01-01T00:00:00+00:00"));
+        assert_eq!(decoded.id, id);
+        assert_eq!(decoded.d, CursorDirection::Next);
+    }
+
+    #[test]
+    fn codec_rejects_tampering() {
+        let codec = CursorCodec::new("secret");
+        let token = codec.encode(&payload(Uuid::new_v4())).unwrap();
+        let (body, signature) = token.split_once('.').unwrap();
+
+        let mut forged = payload(Uuid::new_v4());
+        forged.k = None;
+        let forged_body = URL_SAFE_NO_PAD.encode(serde_json::to_vec(&forged).unwrap());
+        assert!(codec.decode(&format!("{forged_body}.{signature}")).is_err());
+
+        let flipped = if signature.starts_with('A') { "B" } else { "A" };
+        assert!(codec.decode(&format!("{body}.{flipped}{}", &signature[1..])).is_err());
+        assert!(codec.decode(body).is_err());
+        assert!(CursorCodec::new("other secret").decode(&token).is_err());
+    }
+
+    #[test]
+    fn codec_key_is_derived_from_the_secret() {
+        let codec = CursorCodec::new("secret");
+        assert_ne!(codec.key, b"secret");
+        assert_ne!(codec.key, b"cursor.secret");
+        assert_eq!(codec.key.len(), 32);
+    }
+
+    #[test]
+    fn cursor_continues_the_same_query() {
+        let first = parse(&[("sort", "-created_at"), ("filter[username][contains]", "jo")]).unwrap();
+        let id = Uuid::new_v4();
+        let token = first
+            .cursor_for(Some("2024-01-01T00:00:00+00:00".to_string()), id, CursorDirection::Prev)
+            .unwrap();
+
+        let next = parse(&[
+            ("sort", "-created_at"),
+            ("filter[username][contains]", "jo"),
+            ("cursor", &token),
+        ])
+        .unwrap();
+        let cursor = next.cursor.unwrap();
+        assert_eq!(cursor.id, id);
+        assert_eq!(cursor.direction, CursorDirection::Prev);
+        assert!(matches!(cursor.key, Some(Value::ChronoDateTimeWithTimeZone(Some(_)))));
+    }
+
+    #[test]
+    fn cursor_rejects_a_different_sort() {
+        let token = parse(&[("sort", "-created_at")])
+            .unwrap()
+            .cursor_for(Some("2024-01-01T00:00:00+00:00".to_string()), Uuid::new_v4(), CursorDirection::Next)
+            .unwrap();
+        assert_eq!(code(parse(&[("sort", "created_at"), ("cursor", &token)])), "cursor_sort_mismatch");
+        assert_eq!(code(parse(&[("cursor", &token)])), "cursor_sort_mismatch");
+    }
+
+    #[test]
+    fn cursor_rejects_different_filters() {
+        let filtered = [("filter[username][contains]", "jo"), ("filter[deleted_at][is_null]", "true")];
+        let token = parse(&filtered)
+            .unwrap()
+            .cursor_for(None, Uuid::new_v4(), CursorDirection::Next)
+            .unwrap();
+
+        let reordered = [filtered[1], filtered[0], ("cursor", token.as_str())];
+        assert!(parse(&reordered).is_ok());
+        assert_eq!(code(parse(&[("cursor", &token)])), "cursor_filter_mismatch");
+        assert_eq!(
+            code(parse(&[("filter[username][contains]", "ja"), filtered[1], ("cursor", &token)])),
+            "cursor_filter_mismatch"
+        );
+    }
+
+    #[test]
+    fn cursor_rejects_garbage() {
+        assert_eq!(code(parse(&[("cursor", "not-a-cursor")])), "cursor_invalid");
+    }
+}
@@ -0,0 +1,439 @@
+use crate::dto::base::PageMeta;
+use crate::infrastructure::errors::{AppError, AppResult, ValidationMessageError};
+use crate::infrastructure::validation_code::ValidationCode;
+use axum::extract::{FromRequestParts, Query};
+use axum::http::request::Parts;
+use sea_orm::sea_query::{ColumnType, LikeExpr, SimpleExpr};
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, EntityTrait, Order, PaginatorTrait, QueryFilter, QueryOrder,
+    QuerySelect, Select, Value,
+};
+use std::marker::PhantomData;
+
+const DEFAULT_PER_PAGE: u64 = 20;
+/// Databases take the offset as a signed 64 bit integer.
+const MAX_OFFSET: u64 = i64::MAX as u64;
+
+/// What a filterable column holds, deciding which operators apply to it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FieldKind {
+    /// Also matches `contains` and `starts_with`.
+    Text,
+    Other,
+}
+
+/// Whitelist of the columns a list endpoint lets clients sort and filter on.
+pub trait ListSpec: Send + Sync + 'static {
+    type Entity: EntityTrait;
+    const MAX_PER_PAGE: u64 = 100;
+
+    fn sortable(field: &str) -> Option<<Self::Entity as EntityTrait>::Column>;
+    fn filterable(field: &str) -> Option<(<Self::Entity as EntityTrait>::Column, FieldKind)>;
+    fn default_sort() -> Vec<(<Self::Entity as EntityTrait>::Column, Order)>;
+    /// Unique tie breaker used for keyset pagination.
+    fn id_column() -> <Self::Entity as EntityTrait>::Column;
+}
+
+/// Pagination, sorting and filtering parsed from the query string:
+/// `?page=2&per_page=20` or `?limit=20&offset=40`, `sort=-created_at,username`
+/// and `filter[username][contains]=john`.
+pub struct ListQuery<S: ListSpec> {
+    pub page: u64,
+    pub per_page: u64,
+    pub offset: u64,
+    pub sort: Vec<(<S::Entity as EntityTrait>::Column, Order)>,
+    pub filters: Vec<SimpleExpr>,
+    spec: PhantomData<S>,
+}
+
+impl<S, St> FromRequestParts<St> for ListQuery<S>
+where
+    S: ListSpec,
+    St: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &St) -> Result<Self, Self::Rejection> {
+        let Query(pairs) = Query::<Vec<(String, String)>>::try_from_uri(&parts.uri)?;
+        Self::parse(pairs)
+    }
+}
+
+impl<S: ListSpec> ListQuery<S> {
+    pub fn parse(pairs: Vec<(String, String)>) -> AppResult<Self> {
+        let mut page = None;
+        let mut per_page = None;
+        let mut limit = None;
+        let mut offset = None;
+        let mut sort = Vec::new();
+        let mut filters = Vec::new();
+
+        for (key, value) in pairs {
+            match key.as_str() {
+                "page" => page = Some(parse_number(&key, &value)?),
+                "per_page" => per_page = Some(parse_number(&key, &value)?),
+                "limit" => limit = Some(parse_number(&key, &value)?),
+                "offset" => offset = Some(parse_number(&key, &value)?),
+                "sort" => sort.extend(Self::parse_sort(&value)?),
+                _ if key.starts_with("filter[") => filters.push(Self::parse_filter(&key, &value)?),
+                _ => {}
+            }
+        }
+
+        if page.is_some() && offset.is_some() {
+            let message = "offset cannot be combined with page";
+            return Err(invalid("offset", ValidationCode::OFFSET_WITH_PAGE, message).into());
+        }
+        let per_page = limit.or(per_page).unwrap_or(DEFAULT_PER_PAGE);
+        if per_page == 0 || per_page > S::MAX_PER_PAGE {
+            return Err(invalid(
+                "per_page",
+                ValidationCode::RANGE,
+                format!("per_page must be between 1 and {}", S::MAX_PER_PAGE),
+            )
+            .with_param("min", 1)
+            .with_param("max", S::MAX_PER_PAGE)
+            .into());
+        }
+        let offset = match (page, offset) {
+            (Some(0), _) => {
+                return Err(invalid("page", "range", "page starts at 1").with_param("min", 1).into())
+            }
+            (Some(page), _) => {
+                match (page - 1).checked_mul(per_page).filter(|offset| *offset <= MAX_OFFSET) {
+                    Some(offset) => offset,
+                    None => {
+                        let max = MAX_OFFSET / per_page + 1;
+                        let message = format!("page must be between 1 and {max}");
+                        return Err(invalid("page", ValidationCode::RANGE, message)
+                            .with_param("min", 1)
+                            .with_param("max", max)
+                            .into());
+                    }
+                }
+            }
+            (None, Some(offset)) if offset > MAX_OFFSET => {
+                let message = format!("offset must be between 0 and {MAX_OFFSET}");
+                return Err(invalid("offset", ValidationCode::RANGE, message)
+                    .with_param("min", 0)
+                    .with_param("max", MAX_OFFSET)
+                    .into());
+            }
+            (None, offset) => offset.unwrap_or(0),
+        };
+
+        Ok(ListQuery {
+            page: offset / per_page + 1,
+            per_page,
+            offset,
+            sort,
+            filters,
+            spec: PhantomData,
+        })
+    }
+
+    fn parse_sort(value: &str) -> AppResult<Vec<(<S::Entity as EntityTrait>::Column, Order)>> {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|field| !field.is_empty())
+            .map(|field| {
+                let (field, order) = match field.strip_prefix('-') {
+                    Some(field) => (field, Order::Desc),
+                    None => (field, Order::Asc),
+                };
+                S::sortable(field)
+                    .map(|column| (column, order))
+                    .ok_or_else(|| {
+                        invalid("sort", ValidationCode::NOT_SORTABLE, format!("{field} is not sortable"))
+                            .with_param("value", field)
+                            .into()
+                    })
+            })
+            .collect()
+    }
+
+    /// `filter[field]=value` or `filter[field][op]=value`.
+    pub(crate) fn parse_filter(key: &str, value: &str) -> AppResult<SimpleExpr> {
+        let path = key
+            .strip_prefix("filter[")
+            .and_then(|rest| rest.strip_suffix(']'))
+            .ok_or_else(|| invalid(key, ValidationCode::MALFORMED_FILTER, "malformed filter"))?;
+        let (field, op) = match path.split_once("][") {
+            Some((field, op)) => (field, op),
+            None => (path, "eq"),
+        };
+        let (column, kind) = S::filterable(field)
+            .ok_or_else(|| {
+                invalid(key, ValidationCode::NOT_FILTERABLE, format!("{field} is not filterable"))
+                    .with_param("value", field)
+            })?;
+        if matches!(op, "contains" | "starts_with") && kind != FieldKind::Text {
+            let message = format!("{op} only applies to text fields");
+            return Err(invalid(key, ValidationCode::TEXT_OPERATOR, message)
+                .with_param("value", op)
+                .into());
+        }
+
+        let expr = match op {
+            "eq" => column.eq(typed_value(key, column, value)?),
+            "ne" => column.ne(typed_value(key, column, value)?),
+            "gt" => column.gt(typed_value(key, column, value)?),
+            "gte" => column.gte(typed_value(key, column, value)?),
+            "lt" => column.lt(typed_value(key, column, value)?),
+            "lte" => column.lte(typed_value(key, column, value)?),
+            "contains" => column.like(LikeExpr::new(format!("%{}%", escape_like(value))).escape('\\')),
+            "starts_with" => column.like(LikeExpr::new(format!("{}%", escape_like(value))).escape('\\')),
+            "in" => column.is_in(
+                value
+                    .split(',')
+                    .map(|item| typed_value(key, column, item.trim()))
+                    .collect::<AppResult<Vec<_>>>()?,
+            ),
+            "is_null" => match value {
+                "true" => column.is_null(),
+                "false" => column.is_not_null(),
+                _ => {
+                    let message = "is_null expects true or false";
+                    return Err(invalid(key, ValidationCode::BOOLEAN, message).into());
+                }
+            },
+            _ => {
+                let message = format!("unknown filter operator {op}");
+                return Err(invalid(key, ValidationCode::UNKNOWN_OPERATOR, message)
+                    .with_param("value", op)
+                    .into())
+            }
+        };
+        Ok(expr)
+    }
+
+    pub fn apply(&self, mut select: Select<S::Entity>) -> Select<S::Entity> {
+        for filter in &self.filters {
+            select = select.filter(filter.clone());
+        }
+        let sort = if self.sort.is_empty() {
+            S::default_sort()
+        } else {
+            self.sort.clone()
+        };
+        for (column, order) in sort {
+            select = select.order_by(column, order);
+        }
+        select
+    }
+
+    /// Run the filtered select for the requested page, returning the rows and page metadata.
+    pub async fn fetch<C>(
+        &self,
+        db: &C,
+        select: Select<S::Entity>,
+    ) -> AppResult<(Vec<<S::Entity as EntityTrait>::Model>, PageMeta)>
+    where
+        C: ConnectionTrait,
+        <S::Entity as EntityTrait>::Model: Sync,
+    {
+        let select = self.apply(select);
+        let total = select.clone().count(db).await?;
+        let items = select
+            .offset(self.offset)
+            .limit(self.per_page)
+            .all(db)
+            .await?;
+        Ok((items, PageMeta::new(total, self.page, self.per_page)))
+    }
+}
+
+pub(crate) fn invalid(
+    field: impl Into<String>,
+    code: &'static str,
+    message: impl Into<String>,
+) -> ValidationMessageError {
+    ValidationMessageError::new(field, code, message)
+}
+
+/// Match `%` and `_` literally, the patterns are built with `\\` as the escape character.
+fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+pub(crate) fn parse_number(key: &str, value: &str) -> AppResult<u64> {
+    value
+        .parse()
+        .map_err(|_| {
+            invalid(key, ValidationCode::NUMBER, format!("{key} must be a positive number")).into()
+        })
+}
+
+/// Convert the raw query value to the column's type so comparisons bind correctly.
+pub(crate) fn typed_value<C: ColumnTrait>(key: &str, column: C, value: &str) -> AppResult<Value> {
+    let mismatch = |kind: &str| {
+        invalid(key, ValidationCode::TYPE_MISMATCH, format!("{value} is not a valid {kind}"))
+            .with_param("value", value)
+            .with_param("kind", kind)
+    };
+    let value = match column.def().get_column_type() {
+        ColumnType::Uuid => uuid::Uuid::parse_str(value)
+            .map_err(|_| mismatch("uuid"))?
+            .into(),
+        ColumnType::TimestampWithTimeZone | ColumnType::Timestamp | ColumnType::DateTime => {
+            chrono::DateTime::parse_from_rfc3339(value)
+                .map_err(|_| mismatch("RFC 3339 timestamp"))?
+                .into()
+        }
+        ColumnType::TinyInteger
+        | ColumnType::SmallInteger
+        | ColumnType::Integer
+        | ColumnType::BigInteger => value.parse::<i64>().map_err(|_| mismatch("integer"))?.into(),
+        ColumnType::Float | ColumnType::Double | ColumnType::Decimal(_) => {
+            value.parse::<f64>().map_err(|_| mismatch("number"))?.into()
+        }
+        ColumnType::Boolean => value.parse::<bool>().map_err(|_| mismatch("boolean"))?.into(),
+        _ => value.to_string().into(),
+    };
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ListQuery;
+    use crate::infrastructure::errors::{AppError, ValidationMessageError};
+    use crate::repository::user::UserListSpec;
+    use entity::user_account::{self, Entity as UserAccount};
+    use sea_orm::{DbBackend, EntityTrait, Order, QueryTrait};
+
+    fn parse(query: &[(&str, &str)]) -> Result<ListQuery<UserListSpec>, AppError> {
+        ListQuery::parse(
+            query
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+        )
+    }
+
+    fn rejection(query: &[(&str, &str)]) -> ValidationMessageError {
+        match parse(query) {
+            Err(AppError::ValidationMessageError(error)) => error,
+            Err(e) => panic!("unexpected error {e:?}"),
+            Ok(_) => panic!("{query:?} was accepted"),
+        }
+    }
+
+    fn sql(query: &ListQuery<UserListSpec>) -> String {
+        query.apply(UserAccount::find()).build(DbBackend::Sqlite).to_string()
+    }
+
+    #[test]
+    fn defaults_to_the_first_page() {
+        let query = parse(&[]).unwrap();
+        assert_eq!((query.page, query.per_page, query.offset), (1, 20, 0));
+        assert!(sql(&query).ends_with(r#"ORDER BY "user_account"."created_at" DESC"#));
+    }
+
+    #[test]
+    fn page_becomes_an_offset() {
+        let query = parse(&[("page", "3"), ("per_page", "10")]).unwrap();
+        assert_eq!((query.page, query.per_page, query.offset), (3, 10, 20));
+    }
+
+    #[test]
+    fn offset_and_limit_derive_the_page() {
+        let query = parse(&[("offset", "45"), ("limit", "15")]).unwrap();
+        assert_eq!((query.page, query.per_page, query.offset), (4, 15, 45));
+    }
+
+    #[test]
+    fn rejects_out_of_range_pages() {
+        assert_eq!(rejection(&[("page", "0")]).code.as_deref(), Some("range"));
+        assert_eq!(rejection(&[("per_page", "0")]).code.as_deref(), Some("range"));
+        assert_eq!(rejection(&[("limit", "101")]).code.as_deref(), Some("range"));
+        assert_eq!(rejection(&[("page", "-1")]).code.as_deref(), Some("number"));
+        let error = rejection(&[("page", "2"), ("offset", "10")]);
+        assert_eq!((error.field.as_str(), error.code.as_deref()), ("offset", Some("offset_with_page")));
+    }
+
+    #[test]
+    fn rejects_offsets_past_i64() {
+        let error = rejection(&[("page", &u64::MAX.to_string()), ("per_page", "100")]);
+        assert_eq!((error.field.as_str(), error.code.as_deref()), ("page", Some("range")));
+        assert_eq!(error.params["max"], serde_json::json!(i64::MAX as u64 / 100 + 1));
+
+        let error = rejection(&[("offset", &(i64::MAX as u64 + 1).to_string())]);
+        assert_eq!((error.field.as_str(), error.code.as_deref()), ("offset", Some("range")));
+
+        let last = (i64::MAX as u64 / 100 + 1).to_string();
+        assert!(parse(&[("page", &last), ("per_page", "100")]).is_ok());
+        assert!(parse(&[("offset", &i64::MAX.to_string())]).is_ok());
+    }
+
+    #[test]
+    fn parses_sort_fields_in_order() {
+        let query = parse(&[("sort", "-created_at, username")]).unwrap();
+        assert!(matches!(
+            query.sort.as_slice(),
+            [
+                (user_account::Column::CreatedAt, Order::Desc),
+                (user_account::Column::Username, Order::Asc),
+            ]
+        ));
+        assert!(sql(&query).ends_with(
+            r#"ORDER BY "user_account"."created_at" DESC, "user_account"."username" ASC"#
+        ));
+
+        let error = rejection(&[("sort", "password")]);
+        assert_eq!((error.field.as_str(), error.code.as_deref()), ("sort", Some("not_sortable")));
+    }
+
+    #[test]
+    fn parses_filters() {
+        // Bound values are inlined for display only up to the first `ESCAPE '\'`, so it goes last
+        let query = parse(&[
+            ("filter[created_at][gte]", "2024-01-01T00:00:00Z"),
+            ("filter[deleted_at][is_null]", "true"),
+            ("filter[username][contains]", "john"),
+        ])
+        .unwrap();
+        let sql = sql(&query);
+        assert!(sql.contains(r#""user_account"."username" LIKE '%john%'"#), "{sql}");
+        assert!(sql.contains(r#""user_account"."deleted_at" IS NULL"#), "{sql}");
+        assert!(sql.contains(r#""user_account"."created_at" >= '2024-01-01"#), "{sql}");
+    }
+
+    #[test]
+    fn rejects_invalid_filters() {
+        let code = |query: &[(&str, &str)]| rejection(query).code.unwrap_or_default().to_string();
+        assert_eq!(code(&[("filter[password]", "x")]), "not_filterable");
+        assert_eq!(code(&[("filter[username][like]", "x")]), "unknown_operator");
+        assert_eq!(code(&[("filter[created_at]", "yesterday")]), "type_mismatch");
+        assert_eq!(code(&[("filter[deleted_at][is_null]", "yes")]), "boolean");
+        assert_eq!(code(&[("filter[username", "x")]), "malformed_filter");
+    }
+
+    #[test]
+    fn like_operators_only_apply_to_text() {
+        let error = rejection(&[("filter[created_at][contains]", "2024")]);
+        assert_eq!(error.code.as_deref(), Some("text_operator"));
+        assert_eq!(error.params["value"], "contains");
+        let error = rejection(&[("filter[deleted_at][starts_with]", "2024")]);
+        assert_eq!(error.code.as_deref(), Some("text_operator"));
+        assert!(parse(&[("filter[username][starts_with]", "jo")]).is_ok());
+    }
+
+    #[test]
+    fn like_patterns_match_wildcards_literally() {
+        let contains = sql(&parse(&[("filter[username][contains]", "100%_a\\b")]).unwrap());
+        assert!(
+            contains.contains(r#""user_account"."username" LIKE '%100\%\_a\\b%' ESCAPE '\'"#),
+            "{contains}"
+        );
+
+        let starts_with = sql(&parse(&[("filter[username][starts_with]", "a_")]).unwrap());
+        assert!(
+            starts_with.contains(r#""user_account"."username" LIKE 'a\_%' ESCAPE '\'"#),
+            "{starts_with}"
+        );
+    }
+}
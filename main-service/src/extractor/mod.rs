@@ -1 +1,5 @@
+pub mod async_validator;
+pub mod client;
+pub mod keyset;
+pub mod list_query;
 pub mod validator;
\ No newline at end of file
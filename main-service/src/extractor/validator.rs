@@ -1,12 +1,19 @@
-use crate::infrastructure::errors::AppError;
-use axum::extract::rejection::{PathRejection, QueryRejection};
-use axum::extract::{FromRequestParts, Path};
+use crate::infrastructure::config::Config;
+use crate::infrastructure::errors::{
+    AppError, AppResult, ValidationMessageError, ValidationMessageErrors,
+};
+use crate::infrastructure::validation_code::ValidationCode;
+use axum::body::Bytes;
+use axum::extract::multipart::Field;
+use axum::extract::rejection::{FormRejection, PathRejection, QueryRejection};
+use axum::extract::{FromRef, FromRequestParts, Multipart, Path};
 use axum::http::request::Parts;
 use axum::{
     extract::{rejection::JsonRejection, FromRequest, Query, Request},
-    Json,
+    Form, Json,
 };
 use serde::de::DeserializeOwned;
+use std::sync::Arc;
 use validator::Validate;
 
 #[derive(Debug, Clone, Copy, Default)]
@@ -33,7 +40,7 @@ impl<T, S> FromRequestParts<S> for ValidatedQuery<T>
 where
     T: DeserializeOwned + Validate,
     S: Send + Sync,
-    Query<T>: FromRequest<S, Rejection = QueryRejection>,
+    Query<T>: FromRequestParts<S, Rejection = QueryRejection>,
 {
     type Rejection = AppError;
 
@@ -51,7 +58,7 @@ impl<T, S> FromRequestParts<S> for ValidatedPath<T>
 where
     T: DeserializeOwned + Validate + Send,
     S: Send + Sync,
-    Path<T>: FromRequest<S, Rejection = PathRejection>,
+    Path<T>: FromRequestParts<S, Rejection = PathRejection>,
 {
     type Rejection = AppError;
 
@@ -61,3 +68,176 @@ where
         Ok(ValidatedPath(value))
     }
 }
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ValidatedForm<T>(pub T);
+
+impl<T, S> FromRequest<S> for ValidatedForm<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+    Form<T>: FromRequest<S, Rejection = FormRejection>,
+{
+    type Rejection = AppError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Form(value) = Form::<T>::from_request(req, state).await?;
+        value.validate()?;
+        Ok(ValidatedForm(value))
+    }
+}
+
+/// Limits of a file part of a `ValidatedMultipart` body.
+#[derive(Debug, Clone, Copy)]
+pub struct FileLimit {
+    pub max_size: usize,
+    /// Accepted content types, any type when empty.
+    pub content_types: &'static [&'static str],
+}
+
+/// Multipart body whose text fields deserialize into `Self`, like a url-encoded form.
+pub trait MultipartForm: DeserializeOwned + Validate {
+    /// Limits of the file part `name`, `None` when it is a text field.
+    fn file_limit(name: &str, config: &Config) -> Option<FileLimit>;
+
+    /// Names of the file parts that must be sent.
+    fn required_files() -> &'static [&'static str] {
+        &[]
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct UploadedFile {
+    pub name: String,
+    pub file_name: Option<String>,
+    pub content_type: Option<String>,
+    pub bytes: Bytes,
+}
+
+/// File parts of a `ValidatedMultipart` body in the order they were sent.
+#[derive(Debug, Clone, Default)]
+pub struct UploadedFiles(pub Vec<UploadedFile>);
+
+impl UploadedFiles {
+    pub fn get(&self, name: &str) -> Option<&UploadedFile> {
+        self.0.iter().find(|file| file.name == name)
+    }
+
+    pub fn take(&mut self, name: &str) -> Option<UploadedFile> {
+        let index = self.0.iter().position(|file| file.name == name)?;
+        Some(self.0.remove(index))
+    }
+}
+
+/// Validated text fields and the file parts of a `multipart/form-data` body. File parts are
+/// read chunk by chunk and rejected as soon as they break their `FileLimit`.
+#[derive(Debug, Clone, Default)]
+pub struct ValidatedMultipart<T>(pub T, pub UploadedFiles);
+
+impl<T, S> FromRequest<S> for ValidatedMultipart<T>
+where
+    T: MultipartForm,
+    Arc<Config>: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let config = Arc::<Config>::from_ref(state);
+        let mut multipart = Multipart::from_request(req, state).await?;
+        let mut fields = Vec::new();
+        let mut files = UploadedFiles::default();
+        while let Some(field) = multipart.next_field().await? {
+            let name = field.name().unwrap_or_default().to_string();
+            match T::file_limit(&name, &config) {
+                Some(limit) => files.0.push(read_file(field, name, limit).await?),
+                None if field.file_name().is_some() => {
+                    return Err(AppError::ValidationMessageError(ValidationMessageError::new(
+                        name.clone(),
+                        ValidationCode::FILE_UNEXPECTED,
+                        format!("{name} is not accepted as a file"),
+                    )));
+                }
+                None => fields.push((name, field.text().await?)),
+            }
+        }
+
+        // Text fields follow the url-encoded form rules, e.g. numbers are parsed from strings
+        let form = serde_urlencoded::to_string(&fields)
+            .map_err(|e| AppError::InternalServerErrorWithContext(e.to_string()))?;
+        let deserializer = serde_urlencoded::Deserializer::new(form_urlencoded::parse(form.as_bytes()));
+        let value: T = serde_path_to_error::deserialize(deserializer).map_err(invalid_field)?;
+
+        let mut errors = match value.validate() {
+            Ok(()) => Vec::new(),
+            Err(e) => ValidationMessageErrors::of_validation(&e).errors,
+        };
+        for name in T::required_files() {
+            if files.get(name).is_none() {
+                let message = format!("{name} is required");
+                errors.push(ValidationMessageError::new(*name, ValidationCode::REQUIRED, message));
+            }
+        }
+        if !errors.is_empty() {
+            return Err(AppError::ValidationMessageErrors(ValidationMessageErrors::from(errors)));
+        }
+        Ok(ValidatedMultipart(value, files))
+    }
+}
+
+/// Locate a text field that does not deserialize. Missing fields are reported by their parent,
+/// which is the form itself, so their name is taken from the message.
+fn invalid_field(e: serde_path_to_error::Error<serde::de::value::Error>) -> AppError {
+    let message = e.inner().to_string();
+    let missing = message
+        .strip_prefix("missing field `")
+        .and_then(|rest| rest.strip_suffix('`'));
+    let error = match missing {
+        Some(field) => {
+            ValidationMessageError::new(field, ValidationCode::REQUIRED, format!("{field} is required"))
+        }
+        None => {
+            ValidationMessageError::new(e.path().to_string(), ValidationCode::MULTIPART_FIELDS, message)
+        }
+    };
+    AppError::ValidationMessageError(error)
+}
+
+async fn read_file(mut field: Field<'_>, name: String, limit: FileLimit) -> AppResult<UploadedFile> {
+    let content_type = field.content_type().map(str::to_string);
+    if !limit.content_types.is_empty() {
+        let accepted = content_type.as_deref().unwrap_or("application/octet-stream");
+        if !limit.content_types.contains(&accepted) {
+            return Err(AppError::ValidationMessageError(
+                ValidationMessageError::new(
+                    name.clone(),
+                    ValidationCode::FILE_TYPE,
+                    format!("{name} must not be of type {accepted}"),
+                )
+                .with_param("content_type", accepted),
+            ));
+        }
+    }
+
+    let file_name = field.file_name().map(str::to_string);
+    let mut bytes = Vec::new();
+    while let Some(chunk) = field.chunk().await? {
+        if bytes.len() + chunk.len() > limit.max_size {
+            return Err(AppError::ValidationMessageError(
+                ValidationMessageError::new(
+                    name.clone(),
+                    ValidationCode::FILE_TOO_LARGE,
+                    format!("{name} must not exceed {} bytes", limit.max_size),
+                )
+                .with_param("max", limit.max_size),
+            ));
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok(UploadedFile {
+        name,
+        file_name,
+        content_type,
+        bytes: Bytes::from(bytes),
+    })
+}
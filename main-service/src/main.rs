@@ -1,5 +1,5 @@
-use crate::cli::AppRunCli;
-use crate::infrastructure::config::read_config;
+use crate::cli::{AppCommand, AppRunCli, ConfigCommand};
+use crate::infrastructure::config::ConfigSources;
 use crate::logger::Logger;
 use crate::server::AppServer;
 use clap::Parser;
@@ -17,16 +17,28 @@ mod server;
 pub(crate) mod service;
 pub(crate) mod utils;
 mod cli;
+#[cfg(test)]
+mod test_support;
 
 #[tokio::main]
 async fn main() -> Result<(), anyhow::Error> {
     let cli_config = AppRunCli::parse();
     let _guard = Logger::init(cli_config.cargo_env);
 
-    let config = Arc::new(
-        read_config(&cli_config.config, Some(cli_config.cargo_env))
-            .await?,
-    );
+    let sources = ConfigSources::new(cli_config.config.as_deref(), &cli_config.overrides)?;
+    let loaded = sources.load(Some(cli_config.cargo_env));
+    if let Some(AppCommand::Config(ConfigCommand::Check)) = cli_config.command {
+        match loaded {
+            Ok(config) => println!("{}", sources.describe(&config)),
+            Err(e) => {
+                eprintln!("{e}");
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+    let config = Arc::new(loaded?);
+    tracing::debug!("Effective configuration:\n{}", sources.describe(&config));
 
     tracing::info!("Migrating database started");
     let db = AppServer::create_db_conn(&config).await?;
@@ -1,13 +1,31 @@
 use crate::dto::auth::Claims;
+use crate::dto::permission::Permission;
 use crate::infrastructure::errors::{AppError, AppResult};
+use crate::infrastructure::i18n::I18n;
 use crate::infrastructure::state::AppState;
+use crate::repository::impersonation::ImpersonationRepository;
+use crate::repository::session::SessionRepository;
 use crate::repository::user::UserRepository;
 use axum::body::Body;
 use axum::extract::{Request, State};
 use axum::http::header::AUTHORIZATION;
 use axum::middleware::Next;
 use axum::response::Response;
-use jsonwebtoken::{decode, DecodingKey, TokenData, Validation};
+use entity::user_account;
+use jsonwebtoken::errors::ErrorKind;
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, TokenData, Validation};
+use uuid::Uuid;
+
+/// Admin behind the request when `current_user` is being impersonated.
+#[derive(Clone, Debug)]
+pub struct Impersonator {
+    pub admin: user_account::Model,
+    pub audit_id: Uuid,
+}
+
+/// Scope of the bearer token, `None` for a full access token.
+#[derive(Clone, Debug)]
+pub struct TokenScope(pub Option<String>);
 
 pub async fn authentication_middleware(
     State(state): State<AppState>,
@@ -32,23 +50,137 @@ pub async fn authentication_middleware(
         &state.config.jwt_secret,
     ) {
         Ok(data) => data,
+        Err(AppError::TokenExpired) => return Err(AppError::TokenExpired),
         Err(_) => return Err(AppError::Unauthorized),
     };
     // Fetch the user details from the database
-    let current_user = UserRepository::new(state.db)
-        .get_by_id(&token_data.claims.sub)
+    let user_repository = UserRepository::new(state.db.clone(), state.user_cache);
+    let current_user = user_repository
+        .get_principal(&token_data.claims.sub)
         .await
         .map_err(|_| AppError::Forbidden("User not found".to_string()))?;
+
+    let session_id = Uuid::parse_str(&token_data.claims.sid).map_err(|_| AppError::Unauthorized)?;
+    let session = SessionRepository::new(state.db.clone())
+        .with_cache(state.session_cache)
+        .get_active(&session_id, &current_user.id)
+        .await?;
+
+    if let Some(actor) = token_data.claims.act {
+        let audit_id = Uuid::parse_str(&actor.sid).map_err(|_| AppError::Unauthorized)?;
+        let audit = ImpersonationRepository::new(state.db)
+            .get_active(&audit_id)
+            .await?;
+        if audit.admin_id.to_string() != actor.sub || audit.target_user_id != current_user.id {
+            return Err(AppError::Unauthorized);
+        }
+        let admin = user_repository
+            .get_principal(&actor.sub)
+            .await
+            .map_err(|_| AppError::Forbidden("User not found".to_string()))?;
+        Permission::UserImpersonate.ensure(&admin)?;
+
+        tracing::info!(
+            "impersonation {}: admin {} as user {} {} {}",
+            audit_id,
+            admin.id,
+            current_user.id,
+            req.method(),
+            req.uri().path()
+        );
+        req.extensions_mut().insert(Impersonator { admin, audit_id });
+    }
+
+    // The profile locale wins over `Accept-Language`
+    if let Some(locale) = current_user.locale.as_deref().and_then(I18n::negotiate) {
+        I18n::set_current(locale);
+    }
+
+    req.extensions_mut().insert(TokenScope(token_data.claims.scope));
+    req.extensions_mut().insert(session);
     req.extensions_mut().insert(current_user);
     Ok(next.run(req).await)
 }
 
+/// Rejects the request when it is made with an impersonation or scoped token.
+pub async fn require_full_access(req: Request, next: Next) -> AppResult<Response<Body>> {
+    if req.extensions().get::<Impersonator>().is_some() {
+        return Err(AppError::Forbidden(
+            "This operation is not allowed while impersonating".to_string(),
+        ));
+    }
+    if let Some(TokenScope(Some(scope))) = req.extensions().get::<TokenScope>() {
+        return Err(AppError::Forbidden(format!(
+            "This operation is not allowed for {scope} tokens"
+        )));
+    }
+    Ok(next.run(req).await)
+}
+
+/// Rejects the request unless `current_user` holds `permission`. Layered on routes whose
+/// extractors touch the database, so callers lacking it learn nothing from validation errors.
+pub async fn require_permission(
+    State(permission): State<Permission>,
+    req: Request,
+    next: Next,
+) -> AppResult<Response<Body>> {
+    let current_user = req
+        .extensions()
+        .get::<user_account::Model>()
+        .ok_or(AppError::Unauthorized)?;
+    permission.ensure(current_user)?;
+    Ok(next.run(req).await)
+}
+
 pub fn decode_jwt(jwt_token: &str, secret: &str) -> AppResult<TokenData<Claims>> {
     let result = decode(
         jwt_token,
         &DecodingKey::from_secret(secret.as_ref()),
         &Validation::default(),
     )
-    .map_err(|e| AppError::InternalServerErrorWithContext(e.to_string()));
+    .map_err(|e| match e.kind() {
+        ErrorKind::ExpiredSignature => AppError::TokenExpired,
+        _ => AppError::InternalServerErrorWithContext(e.to_string()),
+    });
     result
 }
+
+pub fn encode_jwt(claims: &Claims, secret: &str) -> AppResult<String> {
+    encode(
+        &Header::default(),
+        claims,
+        &EncodingKey::from_secret(secret.as_ref()),
+    )
+    .map_err(|e| AppError::InternalServerErrorWithContext(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_jwt, encode_jwt};
+    use crate::dto::auth::Claims;
+    use crate::infrastructure::errors::AppError;
+
+    fn token(exp: i64) -> String {
+        let now = chrono::Utc::now().timestamp();
+        let claims = Claims {
+            exp: (now + exp) as usize,
+            iat: now as usize,
+            sub: "user".to_string(),
+            sid: "session".to_string(),
+            scope: None,
+            act: None,
+        };
+        encode_jwt(&claims, "secret").unwrap()
+    }
+
+    #[test]
+    fn expired_tokens_are_told_apart() {
+        assert!(decode_jwt(&token(3600), "secret").is_ok());
+        // Past the default leeway of a minute
+        assert!(matches!(decode_jwt(&token(-120), "secret"), Err(AppError::TokenExpired)));
+        assert!(matches!(
+            decode_jwt(&token(3600), "other secret"),
+            Err(AppError::InternalServerErrorWithContext(_))
+        ));
+    }
+}
@@ -0,0 +1,183 @@
+use crate::infrastructure::body_format::{BodyFormat, MAX_BODY_SIZE};
+use crate::infrastructure::errors::AppError;
+use axum::body::{to_bytes, Body, Bytes};
+use axum::extract::{DefaultBodyLimit, FromRequest, Request};
+use axum::http::header::{CONTENT_LENGTH, CONTENT_TYPE, VARY};
+use axum::http::{HeaderValue, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+
+/// Accept MessagePack and CBOR bodies and answer in the format asked for in `Accept`.
+/// Binary bodies are converted to JSON before routing, so every JSON extractor validates and
+/// rejects them exactly like JSON. JSON responses are converted back on the way out.
+///
+/// `MAX_BODY_SIZE` applies to the binary body as sent. The JSON it becomes is usually larger,
+/// so the converted request is exempt from the `DefaultBodyLimit` the extractors apply.
+pub async fn body_format(req: Request, next: Next) -> Response {
+    let accept = BodyFormat::negotiate(req.headers());
+    let mut response = match BodyFormat::of_content_type(req.headers()) {
+        Some(format) if format != BodyFormat::Json => match to_json_request(req, format).await {
+            Ok(req) => next.run(req).await,
+            Err(e) => e.into_response(),
+        },
+        _ => next.run(req).await,
+    };
+    let is_json = BodyFormat::of_content_type(response.headers()) == Some(BodyFormat::Json);
+    if !is_json {
+        return response;
+    }
+    response
+        .headers_mut()
+        .append(VARY, HeaderValue::from_static("accept"));
+    if accept == BodyFormat::Json {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => return AppError::InternalServerErrorWithContext(e.to_string()).into_response(),
+    };
+    let encoded = BodyFormat::Json
+        .decode(&bytes)
+        .and_then(|value| accept.encode(&value));
+    match encoded {
+        Ok(encoded) => {
+            parts.headers.remove(CONTENT_LENGTH);
+            parts
+                .headers
+                .insert(CONTENT_TYPE, HeaderValue::from_static(accept.media_type()));
+            Response::from_parts(parts, Body::from(encoded))
+        }
+        // Not every JSON response is a document, leave those alone
+        Err(_) => Response::from_parts(parts, Body::from(bytes)),
+    }
+}
+
+async fn to_json_request(req: Request, format: BodyFormat) -> Result<Request, AppError> {
+    let (mut parts, body) = req.into_parts();
+    let mut limited = Request::new(body);
+    DefaultBodyLimit::max(MAX_BODY_SIZE).apply(&mut limited);
+    let bytes = Bytes::from_request(limited, &()).await.map_err(|e| match e.status() {
+        StatusCode::PAYLOAD_TOO_LARGE => AppError::PayloadTooLarge(format!(
+            "The request body exceeds {MAX_BODY_SIZE} bytes"
+        )),
+        _ => AppError::UndecodableBody(format!("Failed to read the request body: {}", e.body_text())),
+    })?;
+    let value = format.decode(&bytes).map_err(|e| {
+        AppError::UndecodableBody(format!(
+            "Failed to parse the request body as {}: {e}",
+            format.media_type()
+        ))
+    })?;
+    let json = BodyFormat::Json
+        .encode(&value)
+        .map_err(|e| AppError::UndecodableBody(e.to_string()))?;
+
+    parts.headers.remove(CONTENT_LENGTH);
+    parts.headers.insert(
+        CONTENT_TYPE,
+        HeaderValue::from_static(BodyFormat::Json.media_type()),
+    );
+    let mut req = Request::from_parts(parts, Body::from(json));
+    DefaultBodyLimit::disable().apply(&mut req);
+    Ok(req)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::body_format;
+    use crate::infrastructure::body_format::{BodyFormat, MAX_BODY_SIZE};
+    use crate::infrastructure::errors::AppResult;
+    use axum::body::{to_bytes, Body};
+    use axum::extract::rejection::JsonRejection;
+    use axum::extract::DefaultBodyLimit;
+    use axum::http::header::{ACCEPT, CONTENT_TYPE};
+    use axum::http::{Request, StatusCode};
+    use axum::response::Response;
+    use axum::routing::post;
+    use axum::{middleware, Json, Router};
+    use serde_json::{json, Value};
+    use tower::ServiceExt;
+
+    // Rejections go through `AppError` like in `ValidatedJson`
+    async fn echo(payload: Result<Json<Value>, JsonRejection>) -> AppResult<Json<Value>> {
+        Ok(payload?)
+    }
+
+    async fn length(payload: Result<Json<Value>, JsonRejection>) -> AppResult<Json<Value>> {
+        let Json(value) = payload?;
+        Ok(Json(json!(value["text"].as_str().map(str::len))))
+    }
+
+    fn app() -> Router {
+        Router::new()
+            .route("/echo", post(echo))
+            .route("/length", post(length))
+            .layer(middleware::from_fn(body_format))
+            .layer(DefaultBodyLimit::max(MAX_BODY_SIZE))
+    }
+
+    async fn send(uri: &str, format: BodyFormat, accept: BodyFormat, body: Vec<u8>) -> Response {
+        let request = Request::post(uri)
+            .header(CONTENT_TYPE, format.media_type())
+            .header(ACCEPT, accept.media_type())
+            .body(Body::from(body))
+            .unwrap();
+        app().oneshot(request).await.unwrap()
+    }
+
+    async fn decode(response: Response) -> Value {
+        let format = BodyFormat::of_content_type(response.headers()).unwrap();
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        format.decode(&bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn round_trips_between_every_pair_of_formats() {
+        let value = json!({ "name": "Zoë", "tags": ["a", "b"], "size": 3 });
+        let formats = [BodyFormat::Json, BodyFormat::MessagePack, BodyFormat::Cbor];
+        for format in formats {
+            for accept in formats {
+                let response = send("/echo", format, accept, format.encode(&value).unwrap()).await;
+                assert_eq!(response.status(), StatusCode::OK);
+                assert_eq!(BodyFormat::of_content_type(response.headers()), Some(accept));
+                assert_eq!(decode(response).await, value, "{format:?} -> {accept:?}");
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn binary_bodies_may_grow_past_the_limit_as_json() {
+        // Control characters are escaped as `\u0001` in JSON, six times the MessagePack size
+        let text = "\u{1}".repeat(MAX_BODY_SIZE / 2);
+        let body = BodyFormat::MessagePack.encode(&json!({ "text": text })).unwrap();
+        assert!(body.len() < MAX_BODY_SIZE);
+        let response = send("/length", BodyFormat::MessagePack, BodyFormat::Json, body).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(decode(response).await, json!(text.len()));
+    }
+
+    #[tokio::test]
+    async fn oversized_bodies_are_rejected_with_413() {
+        let text = "a".repeat(MAX_BODY_SIZE);
+        let formats = [
+            (BodyFormat::Json, "REQUEST_JSON_TOO_LARGE"),
+            (BodyFormat::MessagePack, "REQUEST_BODY_TOO_LARGE"),
+            (BodyFormat::Cbor, "REQUEST_BODY_TOO_LARGE"),
+        ];
+        for (format, code) in formats {
+            let body = format.encode(&json!({ "text": text })).unwrap();
+            let response = send("/length", format, BodyFormat::Json, body).await;
+            assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE, "{format:?}");
+            assert_eq!(decode(response).await["error"]["code"], code);
+        }
+    }
+
+    #[tokio::test]
+    async fn undecodable_bodies_are_rejected_with_400() {
+        let response = send("/echo", BodyFormat::Cbor, BodyFormat::Json, vec![0xff, 0x00]).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(decode(response).await["error"]["code"], "REQUEST_INVALID_BODY");
+    }
+}
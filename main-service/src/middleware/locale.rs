@@ -0,0 +1,31 @@
+use crate::infrastructure::i18n::I18n;
+use axum::extract::Request;
+use axum::http::header::{ACCEPT_LANGUAGE, CONTENT_LANGUAGE, VARY};
+use axum::http::HeaderValue;
+use axum::middleware::Next;
+use axum::response::Response;
+
+/// Negotiate the message locale from `Accept-Language` and keep it for the whole request.
+/// Must wrap every other layer so errors rendered outside of handlers are translated too.
+pub async fn locale_middleware(req: Request, next: Next) -> Response {
+    let locale = req
+        .headers()
+        .get(ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(I18n::negotiate)
+        .unwrap_or_else(I18n::source_locale);
+
+    let (mut response, locale) = I18n::scope(locale, async move {
+        let response = next.run(req).await;
+        (response, I18n::current())
+    })
+    .await;
+
+    if let Ok(value) = HeaderValue::from_str(&locale.to_string()) {
+        response.headers_mut().insert(CONTENT_LANGUAGE, value);
+    }
+    response
+        .headers_mut()
+        .append(VARY, HeaderValue::from_static("accept-language"));
+    response
+}
@@ -1 +1,4 @@
-pub mod auth;
\ No newline at end of file
+pub mod auth;
+pub mod body_format;
+pub mod locale;
+pub mod problem;
\ No newline at end of file
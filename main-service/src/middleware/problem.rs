@@ -0,0 +1,65 @@
+use crate::infrastructure::config::Config;
+use crate::infrastructure::errors::AppError;
+use crate::infrastructure::problem::{ErrorFormat, Problem, PROBLEM_JSON};
+use axum::body::{to_bytes, Body};
+use axum::extract::{Request, State};
+use axum::http::header::{CONTENT_LENGTH, CONTENT_TYPE};
+use axum::http::HeaderValue;
+use axum::middleware::Next;
+use axum::response::Response;
+use std::sync::Arc;
+
+/// Plain text bodies of framework errors larger than this are not copied into `detail`.
+const MAX_DETAIL_BYTES: usize = 4 * 1024;
+
+/// Render error responses as `application/problem+json` when configured or requested, and
+/// scope `Config::expose_internal_errors` to the request.
+/// Must wrap the whole router, fallbacks included, to cover every error path.
+pub async fn problem_details(
+    State(config): State<Arc<Config>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let wanted = config.error_format == ErrorFormat::Problem || Problem::is_accepted(req.headers());
+    let instance = req.uri().path().to_string();
+    let response = AppError::scope_internal_errors(config.expose_internal_errors, next.run(req)).await;
+    let status = response.status();
+    if !wanted || !(status.is_client_error() || status.is_server_error()) {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let mut problem = match parts.extensions.remove::<Problem>() {
+        Some(problem) => problem,
+        // Responses produced by axum or tower themselves, e.g. 405 or bare rejections
+        None => {
+            let is_text = parts
+                .headers
+                .get(CONTENT_TYPE)
+                .and_then(|value| value.to_str().ok())
+                .is_some_and(|value| value.starts_with("text/plain"));
+            let detail = match is_text {
+                true => to_bytes(body, MAX_DETAIL_BYTES)
+                    .await
+                    .ok()
+                    .map(|bytes| String::from_utf8_lossy(&bytes).trim().to_string())
+                    .filter(|detail| !detail.is_empty()),
+                false => None,
+            };
+            Problem {
+                detail,
+                ..Problem::of_status(status)
+            }
+        }
+    };
+    problem.instance.get_or_insert(instance);
+
+    let Ok(body) = serde_json::to_vec(&problem) else {
+        return Response::from_parts(parts, Body::empty());
+    };
+    parts.headers.remove(CONTENT_LENGTH);
+    parts
+        .headers
+        .insert(CONTENT_TYPE, HeaderValue::from_static(PROBLEM_JSON));
+    Response::from_parts(parts, Body::from(body))
+}
\ No newline at end of file
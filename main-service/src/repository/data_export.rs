@@ -0,0 +1,152 @@
+use crate::dto::privacy::ExportStatus;
+use crate::infrastructure::db_error::DbFailure;
+use crate::infrastructure::errors::{AppError, AppResult};
+use crate::infrastructure::uuid::generate_uuid;
+use chrono::{TimeDelta, Utc};
+use entity::data_export;
+use entity::prelude::DataExport;
+use sea_orm::sea_query::Expr;
+use sea_orm::ActiveValue::Set;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, IntoActiveModel, QueryFilter,
+    QueryOrder,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub struct DataExportRepository {
+    db: Arc<DatabaseConnection>,
+}
+
+impl DataExportRepository {
+    pub fn new(db: Arc<DatabaseConnection>) -> DataExportRepository {
+        Self { db }
+    }
+
+    /// Queue a new export, refusing while another one for the user is still being built.
+    /// The check only gives the common case a clear answer, a partial unique index on
+    /// in-progress exports settles concurrent requests.
+    pub async fn create(&self, user_id: Uuid) -> AppResult<data_export::Model> {
+        let already_preparing = || AppError::Conflict("an export is already being prepared".to_string());
+        let in_progress = DataExport::find()
+            .filter(data_export::Column::UserId.eq(user_id))
+            .filter(data_export::Column::Status.is_in([
+                ExportStatus::Pending.as_str(),
+                ExportStatus::Running.as_str(),
+            ]))
+            .one(&*self.db)
+            .await?;
+        if in_progress.is_some() {
+            return Err(already_preparing());
+        }
+
+        let export = data_export::ActiveModel {
+            id: Set(generate_uuid()),
+            user_id: Set(user_id),
+            status: Set(ExportStatus::Pending.as_str().to_string()),
+            file_key: Set(None),
+            error: Set(None),
+            created_at: Set(Utc::now().fixed_offset()),
+            completed_at: Set(None),
+            expires_at: Set(None),
+        };
+        match export.insert(&*self.db).await {
+            Err(e) if matches!(DbFailure::of(&e), DbFailure::UniqueViolation { .. }) => {
+                Err(already_preparing())
+            }
+            result => Ok(result?),
+        }
+    }
+
+    pub async fn get(&self, user_id: &Uuid, id: &Uuid) -> AppResult<data_export::Model> {
+        DataExport::find_by_id(*id)
+            .filter(data_export::Column::UserId.eq(*user_id))
+            .one(&*self.db)
+            .await?
+            .ok_or(AppError::NotFound("export not found".to_string()))
+    }
+
+    pub async fn list(&self, user_id: &Uuid) -> AppResult<Vec<data_export::Model>> {
+        Ok(DataExport::find()
+            .filter(data_export::Column::UserId.eq(*user_id))
+            .order_by_desc(data_export::Column::CreatedAt)
+            .all(&*self.db)
+            .await?)
+    }
+
+    /// Exports whose archive is past its retention period, of one user or of everyone.
+    pub async fn list_expired(&self, user_id: Option<&Uuid>) -> AppResult<Vec<data_export::Model>> {
+        let mut query = DataExport::find()
+            .filter(data_export::Column::ExpiresAt.lt(Utc::now().fixed_offset()));
+        if let Some(user_id) = user_id {
+            query = query.filter(data_export::Column::UserId.eq(*user_id));
+        }
+        Ok(query.all(&*self.db).await?)
+    }
+
+    /// Fail every export still pending or running. Their tasks died with the previous process,
+    /// left alone they would block new exports of their users forever.
+    pub async fn fail_interrupted(&self, error: &str) -> AppResult<u64> {
+        let result = DataExport::update_many()
+            .col_expr(data_export::Column::Status, Expr::value(ExportStatus::Failed.as_str()))
+            .col_expr(data_export::Column::Error, Expr::value(error))
+            .col_expr(data_export::Column::CompletedAt, Expr::value(Utc::now().fixed_offset()))
+            .filter(data_export::Column::Status.is_in([
+                ExportStatus::Pending.as_str(),
+                ExportStatus::Running.as_str(),
+            ]))
+            .exec(&*self.db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+
+    pub async fn mark_running(&self, id: &Uuid) -> AppResult<data_export::Model> {
+        self.update(id, |export| {
+            export.status = Set(ExportStatus::Running.as_str().to_string())
+        })
+        .await
+    }
+
+    pub async fn complete(
+        &self,
+        id: &Uuid,
+        file_key: String,
+        retention: TimeDelta,
+    ) -> AppResult<data_export::Model> {
+        let now = Utc::now().fixed_offset();
+        self.update(id, |export| {
+            export.status = Set(ExportStatus::Completed.as_str().to_string());
+            export.file_key = Set(Some(file_key));
+            export.completed_at = Set(Some(now));
+            export.expires_at = Set(Some(now + retention));
+        })
+        .await
+    }
+
+    pub async fn fail(&self, id: &Uuid, error: String) -> AppResult<data_export::Model> {
+        self.update(id, |export| {
+            export.status = Set(ExportStatus::Failed.as_str().to_string());
+            export.error = Set(Some(error));
+            export.completed_at = Set(Some(Utc::now().fixed_offset()));
+        })
+        .await
+    }
+
+    pub async fn delete(&self, id: &Uuid) -> AppResult<()> {
+        DataExport::delete_by_id(*id).exec(&*self.db).await?;
+        Ok(())
+    }
+
+    async fn update<F>(&self, id: &Uuid, apply: F) -> AppResult<data_export::Model>
+    where
+        F: FnOnce(&mut data_export::ActiveModel),
+    {
+        let export = DataExport::find_by_id(*id)
+            .one(&*self.db)
+            .await?
+            .ok_or(AppError::NotFound("export not found".to_string()))?;
+        let mut export = export.into_active_model();
+        apply(&mut export);
+        Ok(export.update(&*self.db).await?)
+    }
+}
@@ -0,0 +1,64 @@
+use crate::infrastructure::errors::{AppError, AppResult};
+use crate::infrastructure::uuid::generate_uuid;
+use chrono::{TimeDelta, Utc};
+use entity::impersonation_audit;
+use entity::prelude::ImpersonationAudit;
+use sea_orm::ActiveValue::Set;
+use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, IntoActiveModel};
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub struct ImpersonationRepository {
+    db: Arc<DatabaseConnection>,
+}
+
+impl ImpersonationRepository {
+    pub fn new(db: Arc<DatabaseConnection>) -> ImpersonationRepository {
+        Self { db }
+    }
+
+    pub async fn start(
+        &self,
+        admin_id: Uuid,
+        target_user_id: Uuid,
+        reason: Option<String>,
+        expires_in: TimeDelta,
+    ) -> AppResult<impersonation_audit::Model> {
+        let now = Utc::now().fixed_offset();
+        let audit = impersonation_audit::ActiveModel {
+            id: Set(generate_uuid()),
+            admin_id: Set(admin_id),
+            target_user_id: Set(target_user_id),
+            reason: Set(reason),
+            started_at: Set(now),
+            expires_at: Set(now + expires_in),
+            ended_at: Set(None),
+        };
+        let audit = audit.insert(&*self.db).await?;
+        tracing::info!(
+            "impersonation {} started: admin {} as user {}",
+            audit.id,
+            admin_id,
+            target_user_id
+        );
+        Ok(audit)
+    }
+
+    /// Returns the session only while it has not been ended and has not expired.
+    pub async fn get_active(&self, id: &Uuid) -> AppResult<impersonation_audit::Model> {
+        let audit = ImpersonationAudit::find_by_id(*id).one(&*self.db).await?;
+        match audit {
+            Some(audit) if audit.ended_at.is_none() && audit.expires_at > Utc::now() => Ok(audit),
+            _ => Err(AppError::Unauthorized),
+        }
+    }
+
+    pub async fn end(&self, id: &Uuid) -> AppResult<()> {
+        let audit = self.get_active(id).await?;
+        let mut audit = audit.into_active_model();
+        audit.ended_at = Set(Some(Utc::now().fixed_offset()));
+        let audit = audit.update(&*self.db).await?;
+        tracing::info!("impersonation {} ended by admin {}", audit.id, audit.admin_id);
+        Ok(())
+    }
+}
@@ -0,0 +1,186 @@
+use crate::dto::base::PageMeta;
+use crate::dto::invitation::InvitationStatus;
+use crate::dto::permission::Permission;
+use crate::extractor::list_query::{FieldKind, ListQuery, ListSpec};
+use crate::infrastructure::errors::{AppError, AppResult};
+use crate::infrastructure::uuid::generate_uuid;
+use chrono::{TimeDelta, Utc};
+use entity::prelude::UserInvitation;
+use entity::user_invitation;
+use sea_orm::ActiveValue::Set;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, EntityTrait, IntoActiveModel,
+    Order, QueryFilter,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub struct InvitationListSpec;
+
+impl ListSpec for InvitationListSpec {
+    type Entity = UserInvitation;
+
+    fn sortable(field: &str) -> Option<user_invitation::Column> {
+        match field {
+            "email" => Some(user_invitation::Column::Email),
+            "created_at" => Some(user_invitation::Column::CreatedAt),
+            "expires_at" => Some(user_invitation::Column::ExpiresAt),
+            _ => None,
+        }
+    }
+
+    fn filterable(field: &str) -> Option<(user_invitation::Column, FieldKind)> {
+        match field {
+            "email" => Some((user_invitation::Column::Email, FieldKind::Text)),
+            "project_id" => Some((user_invitation::Column::ProjectId, FieldKind::Other)),
+            "invited_by" => Some((user_invitation::Column::InvitedBy, FieldKind::Other)),
+            "created_at" => Some((user_invitation::Column::CreatedAt, FieldKind::Other)),
+            "expires_at" => Some((user_invitation::Column::ExpiresAt, FieldKind::Other)),
+            _ => None,
+        }
+    }
+
+    fn default_sort() -> Vec<(user_invitation::Column, Order)> {
+        vec![(user_invitation::Column::CreatedAt, Order::Desc)]
+    }
+
+    fn id_column() -> user_invitation::Column {
+        user_invitation::Column::Id
+    }
+}
+
+pub struct InvitationRepository {
+    db: Arc<DatabaseConnection>,
+}
+
+impl InvitationRepository {
+    pub fn new(db: Arc<DatabaseConnection>) -> InvitationRepository {
+        Self { db }
+    }
+
+    pub async fn create(
+        &self,
+        email: String,
+        permissions: &[Permission],
+        project_id: Option<Uuid>,
+        invited_by: Uuid,
+        token_hash: String,
+        expires_in: TimeDelta,
+    ) -> AppResult<user_invitation::Model> {
+        let same_project = match project_id {
+            Some(project_id) => user_invitation::Column::ProjectId.eq(project_id),
+            None => user_invitation::Column::ProjectId.is_null(),
+        };
+        let pending = UserInvitation::find()
+            .filter(user_invitation::Column::Email.eq(&email))
+            .filter(same_project)
+            .filter(Self::status_condition(InvitationStatus::Pending))
+            .one(&*self.db)
+            .await?;
+        if pending.is_some() {
+            return Err(AppError::Conflict(
+                "a pending invitation for this email already exists, reissue it instead".to_string(),
+            ));
+        }
+
+        let now = Utc::now().fixed_offset();
+        let invitation = user_invitation::ActiveModel {
+            id: Set(generate_uuid()),
+            email: Set(email),
+            token_hash: Set(token_hash),
+            permissions: Set(serde_json::to_value(permissions)?),
+            project_id: Set(project_id),
+            invited_by: Set(invited_by),
+            sent_count: Set(1),
+            last_sent_at: Set(now),
+            created_at: Set(now),
+            expires_at: Set(now + expires_in),
+            accepted_at: Set(None),
+            accepted_user_id: Set(None),
+            revoked_at: Set(None),
+        };
+        Ok(invitation.insert(&*self.db).await?)
+    }
+
+    pub async fn get(&self, id: &Uuid) -> AppResult<user_invitation::Model> {
+        UserInvitation::find_by_id(*id)
+            .one(&*self.db)
+            .await?
+            .ok_or(AppError::NotFound("invitation not found".to_string()))
+    }
+
+    /// The invitation behind a token, as long as it can still be accepted.
+    pub async fn get_pending_by_token(&self, token_hash: &str) -> AppResult<user_invitation::Model> {
+        UserInvitation::find()
+            .filter(user_invitation::Column::TokenHash.eq(token_hash))
+            .filter(Self::status_condition(InvitationStatus::Pending))
+            .one(&*self.db)
+            .await?
+            .ok_or(AppError::BadRequest("invitation is invalid or expired".to_string()))
+    }
+
+    /// List invitations in the given state, limited to one inviter unless `invited_by` is `None`.
+    pub async fn list(
+        &self,
+        query: &ListQuery<InvitationListSpec>,
+        status: InvitationStatus,
+        invited_by: Option<Uuid>,
+    ) -> AppResult<(Vec<user_invitation::Model>, PageMeta)> {
+        let mut select = UserInvitation::find().filter(Self::status_condition(status));
+        if let Some(invited_by) = invited_by {
+            select = select.filter(user_invitation::Column::InvitedBy.eq(invited_by));
+        }
+        query.fetch(&*self.db, select).await
+    }
+
+    pub async fn revoke(&self, id: &Uuid) -> AppResult<user_invitation::Model> {
+        let invitation = self.get(id).await?;
+        if invitation.accepted_at.is_some() {
+            return Err(AppError::BadRequest("invitation was already accepted".to_string()));
+        }
+        let mut invitation = invitation.into_active_model();
+        invitation.revoked_at = Set(Some(Utc::now().fixed_offset()));
+        Ok(invitation.update(&*self.db).await?)
+    }
+
+    /// Replace the token of an open invitation and restart its expiry.
+    pub async fn reissue(
+        &self,
+        id: &Uuid,
+        token_hash: String,
+        expires_in: TimeDelta,
+    ) -> AppResult<user_invitation::Model> {
+        let invitation = self.get(id).await?;
+        if invitation.accepted_at.is_some() || invitation.revoked_at.is_some() {
+            return Err(AppError::BadRequest(
+                "only open invitations can be reissued".to_string(),
+            ));
+        }
+        let now = Utc::now().fixed_offset();
+        let sent_count = invitation.sent_count + 1;
+        let mut invitation = invitation.into_active_model();
+        invitation.token_hash = Set(token_hash);
+        invitation.sent_count = Set(sent_count);
+        invitation.last_sent_at = Set(now);
+        invitation.expires_at = Set(now + expires_in);
+        Ok(invitation.update(&*self.db).await?)
+    }
+
+    pub fn status_condition(status: InvitationStatus) -> Condition {
+        let now = Utc::now().fixed_offset();
+        let open = Condition::all()
+            .add(user_invitation::Column::AcceptedAt.is_null())
+            .add(user_invitation::Column::RevokedAt.is_null());
+        match status {
+            InvitationStatus::Pending => open.add(user_invitation::Column::ExpiresAt.gt(now)),
+            InvitationStatus::Expired => open.add(user_invitation::Column::ExpiresAt.lte(now)),
+            InvitationStatus::Accepted => {
+                Condition::all().add(user_invitation::Column::AcceptedAt.is_not_null())
+            }
+            InvitationStatus::Revoked => {
+                Condition::all().add(user_invitation::Column::RevokedAt.is_not_null())
+            }
+            InvitationStatus::All => Condition::all(),
+        }
+    }
+}
@@ -0,0 +1,207 @@
+use crate::dto::base::CursorMeta;
+use crate::extractor::keyset::{CursorDirection, KeysetQuery};
+use crate::extractor::list_query::ListSpec;
+use crate::infrastructure::errors::{AppError, AppResult};
+use sea_orm::sea_query::{Expr, SimpleExpr};
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, EntityTrait, IntoSimpleExpr, ModelTrait, Order, QueryFilter,
+    QueryOrder, QuerySelect, Select, Value,
+};
+use uuid::Uuid;
+
+/// Rows strictly after `(sort_key, id)` in the given order:
+/// `WHERE (sort_key, id) > (..)` for ascending and `<` for descending.
+pub fn keyset_condition<C: ColumnTrait>(
+    sort: Option<(C, Value)>,
+    id_column: C,
+    id: Uuid,
+    order: &Order,
+) -> SimpleExpr {
+    let (left, right) = match sort {
+        Some((column, key)) => (
+            Expr::tuple([column.into_simple_expr(), id_column.into_simple_expr()]),
+            Expr::tuple([Expr::val(key).into(), Expr::val(id).into()]),
+        ),
+        None => (Expr::expr(id_column.into_simple_expr()), Expr::val(id)),
+    };
+    match order {
+        Order::Desc => left.lt(right),
+        _ => left.gt(right),
+    }
+}
+
+/// Walking backwards reads the reversed order and flips the rows afterwards.
+fn scan_order(order: Order, backward: bool) -> Order {
+    match (backward, order) {
+        (true, Order::Desc) => Order::Asc,
+        (true, _) => Order::Desc,
+        (false, order) => order,
+    }
+}
+
+/// Fetch one page of `select` after the query's cursor, returning cursors for both neighbours.
+pub async fn fetch_keyset<S, C>(
+    db: &C,
+    mut select: Select<S::Entity>,
+    query: &KeysetQuery<S>,
+) -> AppResult<(Vec<<S::Entity as EntityTrait>::Model>, CursorMeta)>
+where
+    S: ListSpec,
+    C: ConnectionTrait,
+{
+    let id_column = S::id_column();
+    let order = query
+        .sort
+        .as_ref()
+        .map(|(_, order)| order.clone())
+        .unwrap_or(Order::Asc);
+    let backward = query
+        .cursor
+        .as_ref()
+        .is_some_and(|cursor| cursor.direction == CursorDirection::Prev);
+    let order = scan_order(order, backward);
+
+    for filter in &query.filters {
+        select = select.filter(filter.clone());
+    }
+    if let Some(cursor) = &query.cursor {
+        let sort = query
+            .sort
+            .as_ref()
+            .zip(cursor.key.clone())
+            .map(|((column, _), key)| (*column, key));
+        select = select.filter(keyset_condition(sort, id_column, cursor.id, &order));
+    }
+    if let Some((column, _)) = &query.sort {
+        select = select.order_by(*column, order.clone());
+    }
+    select = select.order_by(id_column, order);
+
+    let mut rows = select.limit(query.limit + 1).all(db).await?;
+    let has_more = rows.len() as u64 > query.limit;
+    rows.truncate(query.limit as usize);
+    if backward {
+        rows.reverse();
+    }
+
+    let has_next = if backward { query.cursor.is_some() } else { has_more };
+    let has_prev = if backward { has_more } else { query.cursor.is_some() };
+    let next = match rows.last() {
+        Some(row) if has_next => Some(cursor_at::<S>(query, row, CursorDirection::Next)?),
+        _ => None,
+    };
+    let prev = match rows.first() {
+        Some(row) if has_prev => Some(cursor_at::<S>(query, row, CursorDirection::Prev)?),
+        _ => None,
+    };
+
+    Ok((
+        rows,
+        CursorMeta {
+            limit: query.limit,
+            next,
+            prev,
+        },
+    ))
+}
+
+fn cursor_at<S: ListSpec>(
+    query: &KeysetQuery<S>,
+    row: &<S::Entity as EntityTrait>::Model,
+    direction: CursorDirection,
+) -> AppResult<String> {
+    let id = match row.get(S::id_column()) {
+        Value::Uuid(Some(id)) => *id,
+        _ => {
+            return Err(AppError::InternalServerErrorWithContext(
+                "keyset pagination requires a uuid primary key".to_string(),
+            ))
+        }
+    };
+    let key = match &query.sort {
+        Some((column, _)) => Some(key_to_string(row.get(*column))?),
+        None => None,
+    };
+    query.cursor_for(key, id, direction)
+}
+
+/// Textual form of a sort key, parsed back with the column type when the cursor is read.
+fn key_to_string(value: Value) -> AppResult<String> {
+    let key = match value {
+        Value::String(Some(value)) => *value,
+        Value::Uuid(Some(value)) => value.to_string(),
+        Value::ChronoDateTimeWithTimeZone(Some(value)) => value.to_rfc3339(),
+        Value::TinyInt(Some(value)) => value.to_string(),
+        Value::SmallInt(Some(value)) => value.to_string(),
+        Value::Int(Some(value)) => value.to_string(),
+        Value::BigInt(Some(value)) => value.to_string(),
+        Value::Double(Some(value)) => value.to_string(),
+        Value::Bool(Some(value)) => value.to_string(),
+        _ => {
+            return Err(AppError::InternalServerErrorWithContext(
+                "sort column cannot be used for keyset pagination".to_string(),
+            ))
+        }
+    };
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{keyset_condition, scan_order};
+    use entity::user_account::{self, Entity as UserAccount};
+    use sea_orm::{DbBackend, EntityTrait, Order, QueryFilter, QueryTrait, Value};
+    use uuid::Uuid;
+
+    const ID: &str = "6f1c5a9e-0b8e-4c43-9a43-2f0c1b3a7d10";
+
+    fn condition(sort: Option<(user_account::Column, Value)>, order: Order) -> String {
+        let id = Uuid::parse_str(ID).unwrap();
+        let condition = keyset_condition(sort, user_account::Column::Id, id, &order);
+        let sql = UserAccount::find()
+            .filter(condition)
+            .build(DbBackend::Sqlite)
+            .to_string();
+        sql.split_once(" WHERE ").unwrap().1.to_string()
+    }
+
+    fn by_username() -> Option<(user_account::Column, Value)> {
+        Some((user_account::Column::Username, "alice".into()))
+    }
+
+    #[test]
+    fn ascending_reads_rows_after_the_key_and_id() {
+        assert_eq!(
+            condition(by_username(), Order::Asc),
+            format!(r#"("user_account"."username", "user_account"."id") > ('alice', '{ID}')"#)
+        );
+    }
+
+    #[test]
+    fn descending_reads_rows_before_the_key_and_id() {
+        assert_eq!(
+            condition(by_username(), Order::Desc),
+            format!(r#"("user_account"."username", "user_account"."id") < ('alice', '{ID}')"#)
+        );
+    }
+
+    #[test]
+    fn id_only_compares_the_id() {
+        assert_eq!(condition(None, Order::Asc), format!(r#""user_account"."id" > '{ID}'"#));
+        assert_eq!(condition(None, Order::Desc), format!(r#""user_account"."id" < '{ID}'"#));
+    }
+
+    #[test]
+    fn prev_scans_the_reversed_order() {
+        assert!(matches!(scan_order(Order::Asc, false), Order::Asc));
+        assert!(matches!(scan_order(Order::Desc, false), Order::Desc));
+        assert!(matches!(scan_order(Order::Asc, true), Order::Desc));
+        assert!(matches!(scan_order(Order::Desc, true), Order::Asc));
+
+        // A prev cursor on a descending list reads the rows after it, ascending
+        assert_eq!(
+            condition(by_username(), scan_order(Order::Desc, true)),
+            format!(r#"("user_account"."username", "user_account"."id") > ('alice', '{ID}')"#)
+        );
+    }
+}
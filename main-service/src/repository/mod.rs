@@ -1 +1,6 @@
+pub mod data_export;
+pub mod impersonation;
+pub mod invitation;
+pub mod keyset;
+pub mod session;
 pub mod user;
\ No newline at end of file
@@ -0,0 +1,144 @@
+use crate::extractor::client::ClientInfo;
+use crate::infrastructure::errors::{AppError, AppResult};
+use crate::infrastructure::uuid::generate_uuid;
+use chrono::{TimeDelta, Utc};
+use entity::prelude::UserSession;
+use entity::user_session;
+use moka::future::Cache;
+use sea_orm::ActiveValue::Set;
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, IntoActiveModel, QueryFilter,
+    QueryOrder,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// `last_seen_at` is only written when it is older than this, to keep reads cheap.
+const LAST_SEEN_RESOLUTION: TimeDelta = TimeDelta::minutes(1);
+
+pub struct SessionRepository {
+    db: Arc<DatabaseConnection>,
+    cache: Option<Arc<Cache<Uuid, user_session::Model>>>,
+}
+
+impl SessionRepository {
+    pub fn new(db: Arc<DatabaseConnection>) -> SessionRepository {
+        Self { db, cache: None }
+    }
+
+    pub fn with_cache(mut self, cache: Arc<Cache<Uuid, user_session::Model>>) -> SessionRepository {
+        self.cache = Some(cache);
+        self
+    }
+
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        client: &ClientInfo,
+        expires_in: TimeDelta,
+    ) -> AppResult<user_session::Model> {
+        let now = Utc::now().fixed_offset();
+        let session = user_session::ActiveModel {
+            id: Set(generate_uuid()),
+            user_id: Set(user_id),
+            user_agent: Set(client.user_agent.clone()),
+            ip_address: Set(client.ip_address.clone()),
+            created_at: Set(now),
+            last_seen_at: Set(now),
+            expires_at: Set(now + expires_in),
+            revoked_at: Set(None),
+        };
+        Ok(session.insert(&*self.db).await?)
+    }
+
+    /// Returns the session when it belongs to the user and is neither revoked nor expired,
+    /// updating `last_seen_at` along the way.
+    pub async fn get_active(&self, id: &Uuid, user_id: &Uuid) -> AppResult<user_session::Model> {
+        let cached = match &self.cache {
+            Some(cache) => cache.get(id).await,
+            None => None,
+        };
+        let session = match cached {
+            Some(session) => session,
+            None => UserSession::find_by_id(*id)
+                .one(&*self.db)
+                .await?
+                .ok_or(AppError::Unauthorized)?,
+        };
+
+        let now = Utc::now();
+        if session.user_id != *user_id || session.revoked_at.is_some() || session.expires_at <= now {
+            return Err(AppError::Unauthorized);
+        }
+
+        let session = if now - session.last_seen_at.to_utc() > LAST_SEEN_RESOLUTION {
+            let mut active = session.into_active_model();
+            active.last_seen_at = Set(now.fixed_offset());
+            active.update(&*self.db).await?
+        } else {
+            session
+        };
+
+        if let Some(cache) = &self.cache {
+            cache.insert(*id, session.clone()).await;
+        }
+        Ok(session)
+    }
+
+    pub async fn list_active(&self, user_id: &Uuid) -> AppResult<Vec<user_session::Model>> {
+        let sessions = UserSession::find()
+            .filter(user_session::Column::UserId.eq(*user_id))
+            .filter(user_session::Column::RevokedAt.is_null())
+            .filter(user_session::Column::ExpiresAt.gt(Utc::now().fixed_offset()))
+            .order_by_desc(user_session::Column::LastSeenAt)
+            .all(&*self.db)
+            .await?;
+        Ok(sessions)
+    }
+
+    pub async fn revoke(&self, user_id: &Uuid, session_id: &Uuid) -> AppResult<()> {
+        let res = UserSession::update_many()
+            .col_expr(user_session::Column::RevokedAt, Expr::value(Utc::now().fixed_offset()))
+            .filter(user_session::Column::Id.eq(*session_id))
+            .filter(user_session::Column::UserId.eq(*user_id))
+            .filter(user_session::Column::RevokedAt.is_null())
+            .exec(&*self.db)
+            .await?;
+        if res.rows_affected == 0 {
+            return Err(AppError::NotFound("session not found".to_string()));
+        }
+        self.invalidate(&[*session_id]).await;
+        Ok(())
+    }
+
+    /// Revoke every active session of the user except `keep`, returning how many were revoked.
+    pub async fn revoke_others(&self, user_id: &Uuid, keep: &Uuid) -> AppResult<u64> {
+        let ids: Vec<Uuid> = self
+            .list_active(user_id)
+            .await?
+            .into_iter()
+            .map(|session| session.id)
+            .filter(|id| id != keep)
+            .collect();
+        if ids.is_empty() {
+            return Ok(0);
+        }
+
+        let res = UserSession::update_many()
+            .col_expr(user_session::Column::RevokedAt, Expr::value(Utc::now().fixed_offset()))
+            .filter(user_session::Column::Id.is_in(ids.clone()))
+            .exec(&*self.db)
+            .await?;
+        self.invalidate(&ids).await;
+        Ok(res.rows_affected)
+    }
+
+    async fn invalidate(&self, ids: &[Uuid]) {
+        if let Some(cache) = &self.cache {
+            for id in ids {
+                cache.invalidate(id).await;
+            }
+        }
+    }
+}
@@ -1,23 +1,77 @@
-use crate::dto::user::UserNewDto;
+use crate::dto::base::{CursorMeta, PageMeta};
+use crate::dto::permission::Permission;
+use crate::dto::user::{UserNewDto, UserUpdateDto};
+use crate::extractor::keyset::KeysetQuery;
+use crate::extractor::list_query::{FieldKind, ListQuery, ListSpec};
+use crate::infrastructure::cache::UserPrincipalCache;
 use crate::infrastructure::errors::{AppError, AppResult};
+use crate::infrastructure::password::PasswordPolicy;
 use crate::infrastructure::uuid::generate_uuid;
-use argon2::password_hash::rand_core::OsRng;
-use argon2::password_hash::SaltString;
-use argon2::{Argon2, PasswordHasher};
+use crate::repository::keyset::fetch_keyset;
 use entity::prelude::UserAccount;
 use entity::user_account;
 use sea_orm::ActiveValue::Set;
-use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, IntoActiveModel, Order,
+    QueryFilter, SelectModel, Selector,
+};
 use std::sync::Arc;
 use uuid::Uuid;
 
+pub struct UserListSpec;
+
+impl ListSpec for UserListSpec {
+    type Entity = UserAccount;
+
+    fn sortable(field: &str) -> Option<user_account::Column> {
+        match field {
+            "username" => Some(user_account::Column::Username),
+            "created_at" => Some(user_account::Column::CreatedAt),
+            _ => None,
+        }
+    }
+
+    fn filterable(field: &str) -> Option<(user_account::Column, FieldKind)> {
+        match field {
+            "username" => Some((user_account::Column::Username, FieldKind::Text)),
+            "created_at" => Some((user_account::Column::CreatedAt, FieldKind::Other)),
+            "disabled_at" => Some((user_account::Column::DisabledAt, FieldKind::Other)),
+            "deleted_at" => Some((user_account::Column::DeletedAt, FieldKind::Other)),
+            _ => None,
+        }
+    }
+
+    fn default_sort() -> Vec<(user_account::Column, Order)> {
+        vec![(user_account::Column::CreatedAt, Order::Desc)]
+    }
+
+    fn id_column() -> user_account::Column {
+        user_account::Column::Id
+    }
+}
+
 pub struct UserRepository {
     db: Arc<DatabaseConnection>,
+    cache: Arc<UserPrincipalCache>,
 }
 
 impl UserRepository {
-    pub fn new(db: Arc<DatabaseConnection>) -> UserRepository {
-        Self { db }
+    /// The principal cache is required so every write through the repository invalidates it.
+    pub fn new(db: Arc<DatabaseConnection>, cache: Arc<UserPrincipalCache>) -> UserRepository {
+        Self { db, cache }
+    }
+
+    /// Fetch an active user for authentication, going through the principal cache.
+    pub async fn get_principal(&self, user_id: &str) -> AppResult<user_account::Model> {
+        let id = Uuid::parse_str(user_id)?;
+        let load = async {
+            let user = self.get_by_id(user_id).await?;
+            if user.deleted_at.is_some() || user.disabled_at.is_some() {
+                return Err(AppError::BadRequest("user not found".to_string()));
+            }
+            Ok(user)
+        };
+        self.cache.get_or_load(id, load).await
     }
 
     pub async fn get_by_id(&self, user_id: &str) -> AppResult<user_account::Model> {
@@ -39,23 +93,185 @@ impl UserRepository {
         }
     }
 
-    pub async fn create(&self, dto: &UserNewDto) -> AppResult<String> {
+    pub async fn list(
+        &self,
+        query: &ListQuery<UserListSpec>,
+    ) -> AppResult<(Vec<user_account::Model>, PageMeta)> {
+        query.fetch(&*self.db, UserAccount::find()).await
+    }
+
+    pub async fn list_keyset(
+        &self,
+        query: &KeysetQuery<UserListSpec>,
+    ) -> AppResult<(Vec<user_account::Model>, CursorMeta)> {
+        fetch_keyset(&*self.db, UserAccount::find(), query).await
+    }
+
+    /// Every user matching the filters in the requested order, read lazily for streaming exports.
+    pub fn export(
+        &self,
+        query: &ListQuery<UserListSpec>,
+    ) -> Selector<SelectModel<user_account::Model>> {
+        query.apply(UserAccount::find()).into_model()
+    }
+
+    pub async fn create(&self, dto: &UserNewDto, policy: &PasswordPolicy) -> AppResult<String> {
         let id = generate_uuid();
+        let username = dto.username.clone().unwrap_or_default();
+        let password = dto.password.clone().unwrap_or_default();
 
-        let salt = SaltString::generate(&mut OsRng);
-        let argon2 = Argon2::default();
-        let password_hash = argon2
-            .hash_password(dto.username.clone().unwrap().as_ref(), &salt)
-            .unwrap()
-            .to_string();
+        // The unique index decides, it maps to a `unique` error on `username` when two requests race
+        policy.check_strength(&password, Some(&username))?;
+        let password_hash = policy.hash(&password).await?;
 
         let user = user_account::ActiveModel {
             id: Set(id),
-            username: Set(dto.username.clone().unwrap()),
+            username: Set(username),
             password: Set(password_hash),
+            display_name: Set(dto.display_name.clone()),
             ..Default::default()
         };
         let res = UserAccount::insert(user).exec(&*self.db).await?;
         Ok(res.last_insert_id.to_string())
     }
+
+    /// Check a username/password pair, upgrading the stored hash when the policy got stronger.
+    pub async fn verify_credentials(
+        &self,
+        username: &str,
+        password: &str,
+        policy: &PasswordPolicy,
+    ) -> AppResult<user_account::Model> {
+        let Ok(user) = self.get_by_username(username).await else {
+            policy.verify_dummy(password).await?;
+            return Err(AppError::Unauthorized);
+        };
+        // Verify before looking at the account state so inactive users take as long as active ones
+        let verified = policy.verify(password, &user.password).await?;
+        let inactive = user.deleted_at.is_some() || user.disabled_at.is_some();
+        if inactive || !verified {
+            return Err(AppError::Unauthorized);
+        }
+
+        if !policy.needs_rehash(&user.password) {
+            return Ok(user);
+        }
+
+        tracing::info!("rehashing password of user {} with current policy", user.id);
+        let password_hash = policy.hash(password).await?;
+        self.update_password_hash(user, password_hash).await
+    }
+
+    pub async fn change_password(
+        &self,
+        user: user_account::Model,
+        password: &str,
+        policy: &PasswordPolicy,
+    ) -> AppResult<user_account::Model> {
+        policy.check_strength(password, Some(&user.username))?;
+        let password_hash = policy.hash(password).await?;
+        self.update_password_hash(user, password_hash).await
+    }
+
+    async fn update_password_hash(
+        &self,
+        user: user_account::Model,
+        password_hash: String,
+    ) -> AppResult<user_account::Model> {
+        let mut user = user.into_active_model();
+        user.password = Set(password_hash);
+        let user = user.update(&*self.db).await?;
+        self.invalidate(&user.id).await;
+        Ok(user)
+    }
+
+    pub async fn update_username(&self, user_id: &Uuid, username: String) -> AppResult<user_account::Model> {
+        let user = self.get_by_id(&user_id.to_string()).await?;
+        if user.username == username {
+            return Ok(user);
+        }
+        self.update(user_id, |user| user.username = Set(username)).await
+    }
+
+    /// Apply the profile fields present in the payload, leaving the others untouched.
+    pub async fn update_profile(&self, user_id: &Uuid, dto: &UserUpdateDto) -> AppResult<user_account::Model> {
+        self.update(user_id, |user| {
+            if let Some(display_name) = &dto.display_name {
+                user.display_name = Set(Some(display_name.clone()));
+            }
+            if let Some(locale) = &dto.locale {
+                user.locale = Set(Some(locale.clone()));
+            }
+            if let Some(timezone) = &dto.timezone {
+                user.timezone = Set(Some(timezone.clone()));
+            }
+            if let Some(phone) = &dto.phone {
+                user.phone = Set(Some(phone.clone()));
+            }
+            if let Some(accent_color) = &dto.accent_color {
+                user.accent_color = Set(Some(accent_color.clone()));
+            }
+            if let Some(map_extent) = &dto.map_extent {
+                user.map_extent = Set(Some(serde_json::Value::from(map_extent.clone())));
+            }
+            if let Some(location) = &dto.location {
+                user.location = Set(Some(serde_json::Value::Object(location.into())));
+            }
+        })
+        .await
+    }
+
+    pub async fn set_avatar(&self, user_id: &Uuid, avatar_key: Option<String>) -> AppResult<user_account::Model> {
+        self.update(user_id, |user| user.avatar_key = Set(avatar_key)).await
+    }
+
+    pub async fn set_disabled(&self, user_id: &Uuid, disabled: bool) -> AppResult<user_account::Model> {
+        let disabled_at = disabled.then(|| chrono::Utc::now().fixed_offset());
+        self.update(user_id, |user| user.disabled_at = Set(disabled_at)).await
+    }
+
+    pub async fn soft_delete(&self, user_id: &Uuid) -> AppResult<user_account::Model> {
+        let deleted_at = chrono::Utc::now().fixed_offset();
+        self.update(user_id, |user| user.deleted_at = Set(Some(deleted_at))).await
+    }
+
+    pub async fn restore(&self, user_id: &Uuid) -> AppResult<user_account::Model> {
+        self.update(user_id, |user| user.deleted_at = Set(None)).await
+    }
+
+    pub async fn set_permissions(
+        &self,
+        user_id: &Uuid,
+        permissions: &[Permission],
+    ) -> AppResult<user_account::Model> {
+        let permissions = serde_json::to_value(permissions)?;
+        self.update(user_id, |user| user.permissions = Set(permissions)).await
+    }
+
+    async fn update<F>(&self, user_id: &Uuid, apply: F) -> AppResult<user_account::Model>
+    where
+        F: FnOnce(&mut user_account::ActiveModel),
+    {
+        let user = self.get_by_id(&user_id.to_string()).await?;
+        let mut user = user.into_active_model();
+        apply(&mut user);
+        let user = user.update(&*self.db).await?;
+        self.invalidate(user_id).await;
+        Ok(user)
+    }
+
+    /// Whether another user than `except` already signs in with `username`. Only a hint for
+    /// validation, inserts and updates rely on the unique index of `user_account.username`.
+    pub async fn is_username_taken(&self, username: &str, except: Option<&Uuid>) -> AppResult<bool> {
+        let mut query = UserAccount::find().filter(user_account::Column::Username.eq(username));
+        if let Some(user_id) = except {
+            query = query.filter(user_account::Column::Id.ne(*user_id));
+        }
+        Ok(query.one(&*self.db).await?.is_some())
+    }
+
+    /// Drop the cached principal, must be called after every write to a user row.
+    pub async fn invalidate(&self, user_id: &Uuid) {
+        self.cache.invalidate(user_id).await;
+    }
 }
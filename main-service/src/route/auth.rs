@@ -0,0 +1,100 @@
+use crate::dto::auth::{
+    DevicePairingResponse, DeviceTokenPayload, OAuth2Response, SignInPayload, DEVICE_SCOPE,
+};
+use crate::dto::base::BaseResponse;
+use crate::extractor::client::ClientInfo;
+use crate::extractor::validator::ValidatedJson;
+use crate::infrastructure::errors::{AppError, AppResult};
+use crate::infrastructure::state::AppState;
+use crate::middleware::auth::{authentication_middleware, require_full_access};
+use crate::repository::user::UserRepository;
+use crate::service::device::DevicePairingService;
+use crate::service::token::{TokenOptions, TokenService};
+use axum::extract::State;
+use axum::routing::post;
+use axum::{middleware, Extension, Json, Router};
+use entity::user_account;
+
+pub struct AuthRoute;
+
+impl AuthRoute {
+    pub fn init(state: &AppState) -> Router<AppState> {
+        let authenticated = Router::new()
+            .route("/device/pairing", post(Self::create_device_pairing))
+            .route_layer(middleware::from_fn(require_full_access))
+            .route_layer(middleware::from_fn_with_state(
+                state.clone(),
+                authentication_middleware,
+            ));
+
+        Router::new()
+            .route("/sign-in", post(Self::sign_in))
+            .route("/device/token", post(Self::device_token))
+            .merge(authenticated)
+    }
+
+    async fn sign_in(
+        State(state): State<AppState>,
+        client: ClientInfo,
+        ValidatedJson(payload): ValidatedJson<SignInPayload>,
+    ) -> AppResult<Json<BaseResponse<OAuth2Response>>> {
+        let user = UserRepository::new(state.db.clone(), state.user_cache.clone())
+            .verify_credentials(
+                &payload.username.unwrap_or_default(),
+                &payload.password.unwrap_or_default(),
+                &state.config.password_policy,
+            )
+            .await?;
+
+        let token = TokenService::issue(
+            &state,
+            user.id,
+            &client,
+            state.config.jwt_expire,
+            TokenOptions::default(),
+        )
+        .await?;
+        Ok(Json(BaseResponse::success(token)))
+    }
+
+    async fn create_device_pairing(
+        State(state): State<AppState>,
+        Extension(current_user): Extension<user_account::Model>,
+    ) -> AppResult<Json<BaseResponse<DevicePairingResponse>>> {
+        let code = DevicePairingService::create(&state, current_user.id).await;
+        let qr_code = DevicePairingService::render_qr(&code)?;
+        Ok(Json(BaseResponse::success(DevicePairingResponse {
+            code,
+            qr_code,
+            expires_in: state.config.device_pairing_ttl,
+        })))
+    }
+
+    async fn device_token(
+        State(state): State<AppState>,
+        client: ClientInfo,
+        ValidatedJson(payload): ValidatedJson<DeviceTokenPayload>,
+    ) -> AppResult<Json<BaseResponse<OAuth2Response>>> {
+        let user_id =
+            DevicePairingService::redeem(&state, &payload.code.unwrap_or_default()).await?;
+        // The user may have been removed while the code was pending
+        let user = UserRepository::new(state.db.clone(), state.user_cache.clone())
+            .get_principal(&user_id.to_string())
+            .await
+            .map_err(|_| AppError::Unauthorized)?;
+
+        let options = TokenOptions {
+            scope: Some(DEVICE_SCOPE.to_string()),
+            ..Default::default()
+        };
+        let token = TokenService::issue(
+            &state,
+            user.id,
+            &client,
+            state.config.device_token_expire,
+            options,
+        )
+        .await?;
+        Ok(Json(BaseResponse::success(token)))
+    }
+}
@@ -0,0 +1,29 @@
+use crate::dto::base::BaseResponse;
+use crate::infrastructure::error_code::ErrorCode;
+use crate::infrastructure::state::AppState;
+use axum::http::header::CONTENT_TYPE;
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::{Json, Router};
+
+/// Public catalogue of the error codes clients may receive.
+pub struct ErrorCodeRoute;
+
+impl ErrorCodeRoute {
+    pub fn init() -> Router<AppState> {
+        Router::new()
+            .route("/", get(Self::list))
+            .route("/catalogue.md", get(Self::markdown))
+    }
+
+    async fn list() -> Json<BaseResponse<&'static [ErrorCode]>> {
+        Json(BaseResponse::success(ErrorCode::ALL))
+    }
+
+    async fn markdown() -> impl IntoResponse {
+        (
+            [(CONTENT_TYPE, "text/markdown; charset=utf-8")],
+            ErrorCode::catalogue_markdown(),
+        )
+    }
+}
@@ -0,0 +1,141 @@
+use crate::dto::auth::OAuth2Response;
+use crate::dto::base::BaseResponse;
+use crate::dto::invitation::{
+    AcceptInvitationDto, InvitationListParams, InvitationNewDto, InvitationReadResponse,
+};
+use crate::dto::permission::Permission;
+use crate::extractor::client::ClientInfo;
+use crate::extractor::list_query::ListQuery;
+use crate::extractor::validator::{ValidatedJson, ValidatedQuery};
+use crate::infrastructure::errors::AppResult;
+use crate::infrastructure::state::AppState;
+use crate::middleware::auth::{authentication_middleware, require_full_access};
+use crate::repository::invitation::{InvitationListSpec, InvitationRepository};
+use crate::service::invitation::InvitationService;
+use crate::service::token::{TokenOptions, TokenService};
+use axum::extract::{Path, State};
+use axum::routing::{delete, get, post};
+use axum::{middleware, Extension, Json, Router};
+use entity::user_account;
+use uuid::Uuid;
+
+pub struct InvitationRoute;
+
+impl InvitationRoute {
+    pub fn init(state: &AppState) -> Router<AppState> {
+        let authenticated = Router::new()
+            .route("/", post(Self::create))
+            .route("/{invitation_id}", delete(Self::revoke))
+            .route("/{invitation_id}/reissue", post(Self::reissue))
+            .route_layer(middleware::from_fn(require_full_access))
+            .route("/", get(Self::list))
+            .route_layer(middleware::from_fn_with_state(
+                state.clone(),
+                authentication_middleware,
+            ));
+
+        Router::new()
+            .route("/accept", post(Self::accept))
+            .merge(authenticated)
+    }
+
+    fn repository(state: &AppState) -> InvitationRepository {
+        InvitationRepository::new(state.db.clone())
+    }
+
+    async fn create(
+        State(state): State<AppState>,
+        Extension(current_user): Extension<user_account::Model>,
+        ValidatedJson(payload): ValidatedJson<InvitationNewDto>,
+    ) -> AppResult<Json<BaseResponse<InvitationReadResponse>>> {
+        let project_id = payload.project_id();
+        let permissions = payload.permissions.unwrap_or_default();
+        InvitationService::ensure_can_invite(&state, &current_user, &permissions, project_id).await?;
+
+        let (token, token_hash) = InvitationService::generate_token();
+        let invitation = Self::repository(&state)
+            .create(
+                payload.email.unwrap_or_default().trim().to_lowercase(),
+                &permissions,
+                project_id,
+                current_user.id,
+                token_hash,
+                state.config.invitation_expire,
+            )
+            .await?;
+        let invite_url = InvitationService::invite_url(&state, &token);
+        Ok(Json(BaseResponse::success(
+            InvitationReadResponse::from_model(invitation)?.with_invite_url(invite_url),
+        )))
+    }
+
+    /// Invitations in one state, `pending` by default. Only user managers see everyone's.
+    async fn list(
+        State(state): State<AppState>,
+        Extension(current_user): Extension<user_account::Model>,
+        ValidatedQuery(params): ValidatedQuery<InvitationListParams>,
+        query: ListQuery<InvitationListSpec>,
+    ) -> AppResult<Json<BaseResponse<Vec<InvitationReadResponse>>>> {
+        let invited_by =
+            (!Permission::UserManage.granted_to(&current_user)).then_some(current_user.id);
+        let (invitations, meta) = Self::repository(&state)
+            .list(&query, params.status.unwrap_or_default(), invited_by)
+            .await?;
+        let invitations = invitations
+            .into_iter()
+            .map(InvitationReadResponse::from_model)
+            .collect::<AppResult<Vec<_>>>()?;
+        Ok(Json(BaseResponse::paginated(invitations, meta)))
+    }
+
+    async fn revoke(
+        State(state): State<AppState>,
+        Extension(current_user): Extension<user_account::Model>,
+        Path(invitation_id): Path<Uuid>,
+    ) -> AppResult<Json<BaseResponse<InvitationReadResponse>>> {
+        let repository = Self::repository(&state);
+        let invitation = repository.get(&invitation_id).await?;
+        InvitationService::ensure_can_manage(&current_user, &invitation)?;
+        let invitation = repository.revoke(&invitation_id).await?;
+        Ok(Json(BaseResponse::success(InvitationReadResponse::from_model(invitation)?)))
+    }
+
+    /// Issue a new link, the previous one stops working. Nothing is sent to the invitee, the
+    /// caller delivers the returned `invite_url` just like after creating the invitation.
+    async fn reissue(
+        State(state): State<AppState>,
+        Extension(current_user): Extension<user_account::Model>,
+        Path(invitation_id): Path<Uuid>,
+    ) -> AppResult<Json<BaseResponse<InvitationReadResponse>>> {
+        let repository = Self::repository(&state);
+        let invitation = repository.get(&invitation_id).await?;
+        InvitationService::ensure_can_manage(&current_user, &invitation)?;
+
+        let (token, token_hash) = InvitationService::generate_token();
+        let invitation = repository
+            .reissue(&invitation_id, token_hash, state.config.invitation_expire)
+            .await?;
+        let invite_url = InvitationService::invite_url(&state, &token);
+        Ok(Json(BaseResponse::success(
+            InvitationReadResponse::from_model(invitation)?.with_invite_url(invite_url),
+        )))
+    }
+
+    /// Accept the invitation and sign the account in.
+    async fn accept(
+        State(state): State<AppState>,
+        client: ClientInfo,
+        ValidatedJson(payload): ValidatedJson<AcceptInvitationDto>,
+    ) -> AppResult<Json<BaseResponse<OAuth2Response>>> {
+        let (user, _) = InvitationService::accept(&state, payload).await?;
+        let token = TokenService::issue(
+            &state,
+            user.id,
+            &client,
+            state.config.jwt_expire,
+            TokenOptions::default(),
+        )
+        .await?;
+        Ok(Json(BaseResponse::success(token)))
+    }
+}
@@ -1,40 +1,60 @@
+use crate::dto::base::BaseResponse;
+use crate::infrastructure::body_format::MAX_BODY_SIZE;
 use crate::infrastructure::config::Config;
+use crate::infrastructure::error_code::ErrorCode;
+use crate::infrastructure::errors::{AppError, CORRELATION_ID_HEADER};
+use crate::infrastructure::i18n::I18n;
+use crate::infrastructure::problem::Problem;
 use crate::infrastructure::state::AppState;
+use crate::middleware::body_format::body_format;
+use crate::middleware::locale::locale_middleware;
+use crate::middleware::problem::problem_details;
 use crate::route::auth::AuthRoute;
+use crate::route::error_code::ErrorCodeRoute;
+use crate::route::invitation::InvitationRoute;
 use crate::route::user::UserRoute;
+use crate::service::privacy::PrivacyService;
 use axum::error_handling::HandleErrorLayer;
 use axum::http::header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE};
 use axum::http::{Method, StatusCode};
-use axum::response::IntoResponse;
-use axum::{BoxError, Json, Router};
+use axum::response::{IntoResponse, Response};
+use axum::{middleware, BoxError, Json, Router};
 use lazy_static::lazy_static;
 use sea_orm::DatabaseConnection;
-use serde_json::json;
 use std::sync::Arc;
 use std::time::Duration;
 use axum::extract::DefaultBodyLimit;
 use tower::ServiceBuilder;
 use tower::{buffer::BufferLayer, limit::RateLimitLayer};
 use tower_http::cors::{Any, CorsLayer};
+use tower_http::services::ServeDir;
 use tower_http::trace::TraceLayer;
 
 mod auth;
+mod error_code;
+mod invitation;
 mod project;
 mod project_image;
 pub mod user;
 
 lazy_static! {
     static ref HTTP_TIMEOUT: u64 = 30;
+    static ref CACHE_STATS_INTERVAL: Duration = Duration::from_secs(5 * 60);
 }
 
 pub struct AppRoute;
 impl AppRoute {
     pub fn init(db: Arc<DatabaseConnection>, config: Arc<Config>) -> Router {
-        let state = AppState::init( db, config );
+        I18n::init();
+        let state = AppState::init( db, config.clone() );
+        state.user_cache.report_stats(*CACHE_STATS_INTERVAL);
+        PrivacyService::spawn_export_sweep(&state);
 
         let routes = Router::new()
             .nest("/auth", AuthRoute::init(&state))
-            .nest("/users", UserRoute::init(&state))
+            .nest("/error-codes", ErrorCodeRoute::init())
+            .nest("/invitations", InvitationRoute::init(&state))
+            .nest("/users", UserRoute::init(&state));
 
         let cors = CorsLayer::new()
             .allow_origin(Any)
@@ -47,10 +67,17 @@ impl AppRoute {
             ])
             .allow_headers([AUTHORIZATION, ACCEPT, CONTENT_TYPE]);
 
-        Router::new()
-            .nest("/api", routes)
+        let mut router = Router::new().nest("/api", routes);
+        // Uploads kept on local disk are served by the app itself unless a CDN URL is configured
+        if state.config.storage_base_url.starts_with('/') {
+            router = router.nest_service(
+                &state.config.storage_base_url,
+                ServeDir::new(&state.config.storage_path),
+            );
+        }
+
+        router
             .layer(cors)
-            .layer(DefaultBodyLimit::max(15 * 1024 * 1024))
             .layer(
                 ServiceBuilder::new()
                     .layer(TraceLayer::new_for_http())
@@ -60,39 +87,65 @@ impl AppRoute {
             )
             .with_state(state)
             .fallback(Self::handle_404)
+            .layer(middleware::from_fn(body_format))
+            // Outside `body_format`, which lifts the limit for the JSON it converted binary bodies to
+            .layer(DefaultBodyLimit::max(MAX_BODY_SIZE))
+            // Outermost so fallbacks and layer errors are rendered as problems too
+            .layer(middleware::from_fn_with_state(config, problem_details))
+            .layer(middleware::from_fn(locale_middleware))
     }
 
     #[allow(clippy::unused_async)]
-    async fn handle_404() -> impl IntoResponse {
-        (
-            StatusCode::NOT_FOUND,
-            Json(json!({
-            "errors":{
-            "message": vec!(String::from("The requested resource does not exist on this server!")),}
-            })),
+    async fn handle_404() -> Response {
+        let message = I18n::error_message(
+            ErrorCode::ROUTE_NOT_FOUND,
+            String::from("The requested resource does not exist on this server!"),
+        );
+        let status = StatusCode::NOT_FOUND;
+        let code = ErrorCode::ROUTE_NOT_FOUND;
+        Problem::new(status, code, message.clone()).attach(
+            (
+                status,
+                Json(BaseResponse::<()>::error(message, code.code, Some(status.as_u16()))),
+            )
+                .into_response(),
         )
     }
 
     #[allow(clippy::unused_async)]
-    async fn handle_timeout_error(err: BoxError) -> (StatusCode, Json<serde_json::Value>) {
+    async fn handle_timeout_error(err: BoxError) -> Response {
         if err.is::<tower::timeout::error::Elapsed>() {
-            (
-                StatusCode::REQUEST_TIMEOUT,
-                Json(json!({
-                    "error":
-                        format!(
-                            "request took longer than the configured {} second timeout",
-                            *HTTP_TIMEOUT
-                        )
-                })),
-            )
-        } else {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({
-                    "error": format!("unhandled internal error: {}", err)
-                })),
-            )
+            let status = StatusCode::REQUEST_TIMEOUT;
+            let message = I18n::error_message(
+                ErrorCode::REQUEST_TIMEOUT,
+                format!(
+                    "request took longer than the configured {} second timeout",
+                    *HTTP_TIMEOUT
+                ),
+            );
+            let code = ErrorCode::REQUEST_TIMEOUT;
+            let body = BaseResponse::<()>::error(message.clone(), code.code, Some(status.as_u16()));
+            return Problem::new(status, code, message).attach((status, Json(body)).into_response());
         }
+
+        let status = StatusCode::INTERNAL_SERVER_ERROR;
+        let correlation_id = AppError::correlate(err.as_ref());
+        let message = I18n::error_message(
+            ErrorCode::SERVICE_ERROR,
+            AppError::public_message(format!("unhandled internal error: {}", err)),
+        );
+        let code = ErrorCode::SERVICE_ERROR;
+        let body = BaseResponse::<()>::error(message.clone(), code.code, Some(status.as_u16()))
+            .with_correlation_id(correlation_id.clone());
+        Problem::new(status, code, message)
+            .with_correlation_id(correlation_id.clone())
+            .attach(
+                (
+                    status,
+                    [(CORRELATION_ID_HEADER, correlation_id)],
+                    Json(body),
+                )
+                    .into_response(),
+            )
     }
-}
+}
\ No newline at end of file
@@ -0,0 +1,594 @@
+use crate::dto::auth::{ActorClaims, ImpersonationPayload, OAuth2Response};
+use crate::dto::base::{BaseResponse, ResponseMeta};
+use crate::dto::permission::Permission;
+use crate::dto::privacy::{DataExportResponse, EraseAccountDto, EraseUserDto};
+use crate::dto::session::SessionReadResponse;
+use crate::dto::user::{
+    AvatarUploadForm, ChangePasswordDto, UserExportRow, UserNewDto, UserPermissionsDto,
+    UserReadResponse, UserUpdateDto,
+};
+use crate::extractor::async_validator::AsyncValidated;
+use crate::extractor::client::ClientInfo;
+use crate::extractor::keyset::Paging;
+use crate::extractor::list_query::ListQuery;
+use crate::extractor::validator::{ValidatedJson, ValidatedMultipart, ValidatedQuery};
+use crate::infrastructure::errors::{AppError, AppResult, ValidationMessageError};
+use crate::infrastructure::row_stream::{RowStream, StreamParams};
+use crate::infrastructure::state::AppState;
+use crate::infrastructure::validation_code::ValidationCode;
+use crate::middleware::auth::{
+    authentication_middleware, require_full_access, require_permission, Impersonator,
+};
+use crate::repository::data_export::DataExportRepository;
+use crate::repository::impersonation::ImpersonationRepository;
+use crate::repository::session::SessionRepository;
+use crate::repository::user::{UserListSpec, UserRepository};
+use crate::service::avatar::AvatarService;
+use crate::service::privacy::PrivacyService;
+use crate::service::token::{TokenOptions, TokenService};
+use axum::extract::{Path, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{delete, get, patch, post, put};
+use axum::{middleware, Extension, Json, Router};
+use entity::{project_data, user_account, user_session};
+use sea_orm::ModelTrait;
+use uuid::Uuid;
+
+pub struct UserRoute;
+
+impl UserRoute {
+    pub fn init(state: &AppState) -> Router<AppState> {
+        // Operations impersonating admins and device scoped tokens must never perform
+        let sensitive = Router::new()
+            .route(
+                "/",
+                post(Self::create).route_layer(middleware::from_fn_with_state(
+                    Permission::UserManage,
+                    require_permission,
+                )),
+            )
+            .route("/export", get(Self::export))
+            .route("/me", patch(Self::update_me).delete(Self::erase_me))
+            .route("/me/avatar", put(Self::upload_avatar).delete(Self::delete_avatar))
+            .route("/me/exports", post(Self::request_export))
+            .route("/me/exports/{export_id}/download", get(Self::download_export))
+            .route("/me/password", put(Self::change_password))
+            .route("/me/project-data/export", get(Self::export_project_data))
+            .route("/me/sessions", delete(Self::revoke_other_sessions))
+            .route("/me/sessions/{session_id}", delete(Self::revoke_session))
+            .route("/{user_id}", delete(Self::soft_delete))
+            .route("/{user_id}/disable", post(Self::disable))
+            .route("/{user_id}/enable", post(Self::enable))
+            .route("/{user_id}/erase", post(Self::erase))
+            .route("/{user_id}/restore", post(Self::restore))
+            .route("/{user_id}/permissions", put(Self::set_permissions))
+            .route("/{user_id}/impersonate", post(Self::impersonate))
+            .route_layer(middleware::from_fn(require_full_access));
+
+        Router::new()
+            .route("/", get(Self::list))
+            .route("/me", get(Self::me))
+            .route("/me/exports", get(Self::list_exports))
+            .route("/me/exports/{export_id}", get(Self::get_export))
+            .route("/me/impersonation", delete(Self::end_impersonation))
+            .route("/me/sessions", get(Self::list_sessions))
+            .route("/{user_id}", get(Self::get))
+            .merge(sensitive)
+            .route_layer(middleware::from_fn_with_state(
+                state.clone(),
+                authentication_middleware,
+            ))
+    }
+
+    fn repository(state: &AppState) -> UserRepository {
+        UserRepository::new(state.db.clone(), state.user_cache.clone())
+    }
+
+    async fn me(
+        State(state): State<AppState>,
+        Extension(current_user): Extension<user_account::Model>,
+    ) -> AppResult<Json<BaseResponse<UserReadResponse>>> {
+        Ok(Json(BaseResponse::success(UserReadResponse::from_model(current_user, &*state.storage)?)))
+    }
+
+    async fn update_me(
+        State(state): State<AppState>,
+        Extension(current_user): Extension<user_account::Model>,
+        AsyncValidated(Json(payload)): AsyncValidated<Json<UserUpdateDto>>,
+    ) -> AppResult<Json<BaseResponse<UserReadResponse>>> {
+        let repository = Self::repository(&state);
+        if let Some(username) = &payload.username {
+            repository
+                .update_username(&current_user.id, username.clone())
+                .await?;
+        }
+        let user = repository.update_profile(&current_user.id, &payload).await?;
+        Ok(Json(BaseResponse::success(UserReadResponse::from_model(user, &*state.storage)?)))
+    }
+
+    async fn upload_avatar(
+        State(state): State<AppState>,
+        Extension(current_user): Extension<user_account::Model>,
+        ValidatedMultipart(_, mut files): ValidatedMultipart<AvatarUploadForm>,
+    ) -> AppResult<Json<BaseResponse<UserReadResponse>>> {
+        // Listed in `required_files`, the extractor rejects bodies without it
+        let avatar = files.take("avatar").map(|file| file.bytes.to_vec()).unwrap_or_default();
+        let key = AvatarService::store(&state, &current_user.id, avatar).await?;
+        let user = Self::repository(&state)
+            .set_avatar(&current_user.id, Some(key))
+            .await?;
+        if let Some(previous) = current_user.avatar_key {
+            Self::discard_avatar(&state, &previous).await;
+        }
+        Ok(Json(BaseResponse::success(UserReadResponse::from_model(user, &*state.storage)?)))
+    }
+
+    async fn delete_avatar(
+        State(state): State<AppState>,
+        Extension(current_user): Extension<user_account::Model>,
+    ) -> AppResult<Json<BaseResponse<UserReadResponse>>> {
+        let user = Self::repository(&state)
+            .set_avatar(&current_user.id, None)
+            .await?;
+        if let Some(previous) = current_user.avatar_key {
+            Self::discard_avatar(&state, &previous).await;
+        }
+        Ok(Json(BaseResponse::success(UserReadResponse::from_model(user, &*state.storage)?)))
+    }
+
+    /// The row no longer points at the old files, failing to delete them only leaves garbage.
+    async fn discard_avatar(state: &AppState, key: &str) {
+        if let Err(e) = AvatarService::remove(state, key).await {
+            tracing::warn!("failed to delete avatar {key}: {e}");
+        }
+    }
+
+    async fn change_password(
+        State(state): State<AppState>,
+        Extension(current_user): Extension<user_account::Model>,
+        Extension(current_session): Extension<user_session::Model>,
+        ValidatedJson(payload): ValidatedJson<ChangePasswordDto>,
+    ) -> AppResult<Json<BaseResponse<UserReadResponse>>> {
+        let policy = &state.config.password_policy;
+        let current_password = payload.current_password.unwrap_or_default();
+        if !policy.verify(&current_password, &current_user.password).await? {
+            return Err(AppError::ValidationMessageError(ValidationMessageError::new(
+                "current_password",
+                ValidationCode::PASSWORD_INCORRECT,
+                "current password is incorrect",
+            )));
+        }
+
+        let user = Self::repository(&state)
+            .change_password(current_user, &payload.new_password.unwrap_or_default(), policy)
+            .await?;
+        // Everywhere else has to sign in again with the new password
+        SessionRepository::new(state.db.clone())
+            .with_cache(state.session_cache.clone())
+            .revoke_others(&user.id, &current_session.id)
+            .await?;
+        Ok(Json(BaseResponse::success(UserReadResponse::from_model(user, &*state.storage)?)))
+    }
+
+    async fn request_export(
+        State(state): State<AppState>,
+        Extension(current_user): Extension<user_account::Model>,
+    ) -> AppResult<(StatusCode, Json<BaseResponse<DataExportResponse>>)> {
+        let export = PrivacyService::request_export(&state, current_user.id).await?;
+        Ok((
+            StatusCode::ACCEPTED,
+            Json(BaseResponse::success(DataExportResponse::from_model(export)?)),
+        ))
+    }
+
+    async fn list_exports(
+        State(state): State<AppState>,
+        Extension(current_user): Extension<user_account::Model>,
+    ) -> AppResult<Json<BaseResponse<Vec<DataExportResponse>>>> {
+        let exports = DataExportRepository::new(state.db)
+            .list(&current_user.id)
+            .await?
+            .into_iter()
+            .map(DataExportResponse::from_model)
+            .collect::<AppResult<Vec<_>>>()?;
+        Ok(Json(BaseResponse::success(exports)))
+    }
+
+    async fn get_export(
+        State(state): State<AppState>,
+        Extension(current_user): Extension<user_account::Model>,
+        Path(export_id): Path<Uuid>,
+    ) -> AppResult<Json<BaseResponse<DataExportResponse>>> {
+        let export = DataExportRepository::new(state.db)
+            .get(&current_user.id, &export_id)
+            .await?;
+        Ok(Json(BaseResponse::success(DataExportResponse::from_model(export)?)))
+    }
+
+    async fn download_export(
+        State(state): State<AppState>,
+        Extension(current_user): Extension<user_account::Model>,
+        Path(export_id): Path<Uuid>,
+    ) -> AppResult<Response> {
+        let export = DataExportRepository::new(state.db.clone())
+            .get(&current_user.id, &export_id)
+            .await?;
+        let expired = export
+            .expires_at
+            .is_some_and(|expires_at| expires_at < chrono::Utc::now());
+        let Some(file_key) = export.file_key.filter(|_| !expired) else {
+            return Err(AppError::NotFound("export is not available".to_string()));
+        };
+        let archive = state.private_storage.get(&file_key).await?;
+        let disposition = format!("attachment; filename=\"export-{export_id}.json\"");
+        Ok((
+            [
+                (header::CONTENT_TYPE, "application/json".to_string()),
+                (header::CONTENT_DISPOSITION, disposition),
+            ],
+            archive,
+        )
+            .into_response())
+    }
+
+    async fn erase_me(
+        State(state): State<AppState>,
+        Extension(current_user): Extension<user_account::Model>,
+        ValidatedJson(payload): ValidatedJson<EraseAccountDto>,
+    ) -> AppResult<Json<BaseResponse>> {
+        let password = payload.password.unwrap_or_default();
+        if !state.config.password_policy.verify(&password, &current_user.password).await? {
+            return Err(AppError::ValidationMessageError(ValidationMessageError::new(
+                "password",
+                ValidationCode::PASSWORD_INCORRECT,
+                "password is incorrect",
+            )));
+        }
+        PrivacyService::erase(&state, &current_user.id, payload.purge_content.unwrap_or(false))
+            .await?;
+        Ok(Json(BaseResponse::success(())))
+    }
+
+    async fn erase(
+        State(state): State<AppState>,
+        Extension(current_user): Extension<user_account::Model>,
+        Path(user_id): Path<Uuid>,
+        ValidatedJson(payload): ValidatedJson<EraseUserDto>,
+    ) -> AppResult<Json<BaseResponse>> {
+        Self::ensure_manageable(&current_user, &user_id)?;
+        PrivacyService::erase(&state, &user_id, payload.purge_content.unwrap_or(false)).await?;
+        Ok(Json(BaseResponse::success(())))
+    }
+
+    async fn list(
+        State(state): State<AppState>,
+        Extension(current_user): Extension<user_account::Model>,
+        paging: Paging<UserListSpec>,
+    ) -> AppResult<Json<BaseResponse<Vec<UserReadResponse>>>> {
+        Permission::UserManage.ensure(&current_user)?;
+        let repository = Self::repository(&state);
+        let (users, meta): (_, ResponseMeta) = match paging {
+            Paging::Offset(query) => {
+                let (users, meta) = repository.list(&query).await?;
+                (users, meta.into())
+            }
+            Paging::Keyset(query) => {
+                let (users, meta) = repository.list_keyset(&query).await?;
+                (users, meta.into())
+            }
+        };
+        let users = users
+            .into_iter()
+            .map(|user| UserReadResponse::from_model(user, &*state.storage))
+            .collect::<AppResult<Vec<_>>>()?;
+        Ok(Json(BaseResponse::paginated(users, meta)))
+    }
+
+    /// All users matching the list filters, streamed as `?format=ndjson` or `?format=csv`.
+    async fn export(
+        State(state): State<AppState>,
+        Extension(current_user): Extension<user_account::Model>,
+        ValidatedQuery(params): ValidatedQuery<StreamParams>,
+        query: ListQuery<UserListSpec>,
+    ) -> AppResult<Response> {
+        Permission::UserManage.ensure(&current_user)?;
+        let users = Self::repository(&state).export(&query);
+        Ok(RowStream::response(state.db, users, params.format, "users", |user| {
+            Ok(UserExportRow::from_model(user))
+        }))
+    }
+
+    async fn export_project_data(
+        State(state): State<AppState>,
+        Extension(current_user): Extension<user_account::Model>,
+        ValidatedQuery(params): ValidatedQuery<StreamParams>,
+    ) -> AppResult<Response> {
+        let rows = current_user.find_related(project_data::Entity).into_json();
+        Ok(RowStream::response(state.db, rows, params.format, "project-data", Ok))
+    }
+
+    async fn get(
+        State(state): State<AppState>,
+        Extension(current_user): Extension<user_account::Model>,
+        Path(user_id): Path<Uuid>,
+    ) -> AppResult<Json<BaseResponse<UserReadResponse>>> {
+        Permission::UserManage.ensure(&current_user)?;
+        let user = Self::repository(&state).get_by_id(&user_id.to_string()).await?;
+        Ok(Json(BaseResponse::success(UserReadResponse::from_model(user, &*state.storage)?)))
+    }
+
+    async fn create(
+        State(state): State<AppState>,
+        AsyncValidated(Json(payload)): AsyncValidated<Json<UserNewDto>>,
+    ) -> AppResult<Json<BaseResponse<UserReadResponse>>> {
+        // `Permission::UserManage` is enforced by the route layer, before the username lookup
+        let repository = Self::repository(&state);
+        let user_id = repository
+            .create(&payload, &state.config.password_policy)
+            .await?;
+        let user = repository.get_by_id(&user_id).await?;
+        Ok(Json(BaseResponse::success(UserReadResponse::from_model(user, &*state.storage)?)))
+    }
+
+    async fn disable(
+        State(state): State<AppState>,
+        Extension(current_user): Extension<user_account::Model>,
+        Path(user_id): Path<Uuid>,
+    ) -> AppResult<Json<BaseResponse<UserReadResponse>>> {
+        Self::ensure_manageable(&current_user, &user_id)?;
+        let user = Self::repository(&state).set_disabled(&user_id, true).await?;
+        Ok(Json(BaseResponse::success(UserReadResponse::from_model(user, &*state.storage)?)))
+    }
+
+    async fn enable(
+        State(state): State<AppState>,
+        Extension(current_user): Extension<user_account::Model>,
+        Path(user_id): Path<Uuid>,
+    ) -> AppResult<Json<BaseResponse<UserReadResponse>>> {
+        Self::ensure_manageable(&current_user, &user_id)?;
+        let user = Self::repository(&state).set_disabled(&user_id, false).await?;
+        Ok(Json(BaseResponse::success(UserReadResponse::from_model(user, &*state.storage)?)))
+    }
+
+    async fn soft_delete(
+        State(state): State<AppState>,
+        Extension(current_user): Extension<user_account::Model>,
+        Path(user_id): Path<Uuid>,
+    ) -> AppResult<Json<BaseResponse<UserReadResponse>>> {
+        Self::ensure_manageable(&current_user, &user_id)?;
+        let user = Self::repository(&state).soft_delete(&user_id).await?;
+        Ok(Json(BaseResponse::success(UserReadResponse::from_model(user, &*state.storage)?)))
+    }
+
+    async fn restore(
+        State(state): State<AppState>,
+        Extension(current_user): Extension<user_account::Model>,
+        Path(user_id): Path<Uuid>,
+    ) -> AppResult<Json<BaseResponse<UserReadResponse>>> {
+        Self::ensure_manageable(&current_user, &user_id)?;
+        let user = Self::repository(&state).restore(&user_id).await?;
+        Ok(Json(BaseResponse::success(UserReadResponse::from_model(user, &*state.storage)?)))
+    }
+
+    async fn set_permissions(
+        State(state): State<AppState>,
+        Extension(current_user): Extension<user_account::Model>,
+        Path(user_id): Path<Uuid>,
+        ValidatedJson(payload): ValidatedJson<UserPermissionsDto>,
+    ) -> AppResult<Json<BaseResponse<UserReadResponse>>> {
+        Self::ensure_manageable(&current_user, &user_id)?;
+        let permissions = payload.permissions.unwrap_or_default();
+        let user = Self::repository(&state)
+            .set_permissions(&user_id, &permissions)
+            .await?;
+        Ok(Json(BaseResponse::success(UserReadResponse::from_model(user, &*state.storage)?)))
+    }
+
+    /// Admins may manage other accounts but never lock themselves out.
+    fn ensure_manageable(current_user: &user_account::Model, user_id: &Uuid) -> AppResult<()> {
+        Permission::UserManage.ensure(current_user)?;
+        if current_user.id == *user_id {
+            return Err(AppError::BadRequest(
+                "Cannot change the status of your own account".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    async fn impersonate(
+        State(state): State<AppState>,
+        Extension(current_user): Extension<user_account::Model>,
+        client: ClientInfo,
+        Path(user_id): Path<Uuid>,
+        ValidatedJson(payload): ValidatedJson<ImpersonationPayload>,
+    ) -> AppResult<Json<BaseResponse<OAuth2Response>>> {
+        Permission::UserImpersonate.ensure(&current_user)?;
+        if user_id == current_user.id {
+            return Err(AppError::BadRequest("Cannot impersonate yourself".to_string()));
+        }
+
+        let target = Self::repository(&state)
+            .get_principal(&user_id.to_string())
+            .await?;
+        if !Permission::of(&target).is_empty() {
+            return Err(AppError::Forbidden(
+                "Cannot impersonate a user holding permissions".to_string(),
+            ));
+        }
+
+        let expires_in = state.config.impersonation_expire;
+        let audit = ImpersonationRepository::new(state.db.clone())
+            .start(current_user.id, target.id, payload.reason, expires_in)
+            .await?;
+
+        let act = ActorClaims {
+            sub: current_user.id.to_string(),
+            sid: audit.id.to_string(),
+        };
+        let options = TokenOptions {
+            act: Some(act),
+            ..Default::default()
+        };
+        let token = TokenService::issue(&state, target.id, &client, expires_in, options).await?;
+        Ok(Json(BaseResponse::success(token)))
+    }
+
+    async fn end_impersonation(
+        State(state): State<AppState>,
+        impersonator: Option<Extension<Impersonator>>,
+    ) -> AppResult<Json<BaseResponse>> {
+        let Some(Extension(impersonator)) = impersonator else {
+            return Err(AppError::BadRequest("Not impersonating".to_string()));
+        };
+        ImpersonationRepository::new(state.db)
+            .end(&impersonator.audit_id)
+            .await?;
+        Ok(Json(BaseResponse::success(())))
+    }
+
+    async fn list_sessions(
+        State(state): State<AppState>,
+        Extension(current_user): Extension<user_account::Model>,
+        Extension(current_session): Extension<user_session::Model>,
+    ) -> AppResult<Json<BaseResponse<Vec<SessionReadResponse>>>> {
+        let sessions = SessionRepository::new(state.db)
+            .list_active(&current_user.id)
+            .await?
+            .into_iter()
+            .map(|session| SessionReadResponse::from_model(session, &current_session.id))
+            .collect::<AppResult<Vec<_>>>()?;
+        Ok(Json(BaseResponse::success(sessions)))
+    }
+
+    async fn revoke_session(
+        State(state): State<AppState>,
+        Extension(current_user): Extension<user_account::Model>,
+        Path(session_id): Path<Uuid>,
+    ) -> AppResult<Json<BaseResponse>> {
+        SessionRepository::new(state.db)
+            .with_cache(state.session_cache)
+            .revoke(&current_user.id, &session_id)
+            .await?;
+        Ok(Json(BaseResponse::success(())))
+    }
+
+    async fn revoke_other_sessions(
+        State(state): State<AppState>,
+        Extension(current_user): Extension<user_account::Model>,
+        Extension(current_session): Extension<user_session::Model>,
+    ) -> AppResult<Json<BaseResponse<u64>>> {
+        let revoked = SessionRepository::new(state.db)
+            .with_cache(state.session_cache)
+            .revoke_others(&current_user.id, &current_session.id)
+            .await?;
+        Ok(Json(BaseResponse::success(revoked)))
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::UserRoute;
+    use crate::infrastructure::state::AppState;
+    use crate::test_support;
+    use axum::body::{to_bytes, Body};
+    use axum::http::header::{CONTENT_DISPOSITION, CONTENT_TYPE};
+    use axum::http::{Request, StatusCode};
+    use axum::response::Response;
+    use axum::routing::get;
+    use axum::{Extension, Router};
+    use entity::{project_data, user_account};
+    use serde_json::{json, Value};
+    use tower::ServiceExt;
+
+    async fn state() -> AppState {
+        test_support::sqlite_state(|schema| {
+            vec![
+                schema.create_table_from_entity(user_account::Entity),
+                schema.create_table_from_entity(project_data::Entity),
+            ]
+        })
+        .await
+    }
+
+    /// Both exports as `current_user`, who `authentication_middleware` would have put there.
+    async fn send(state: &AppState, current_user: &user_account::Model, uri: &str) -> Response {
+        let app = Router::new()
+            .route("/users/export", get(UserRoute::export))
+            .route("/users/me/project-data/export", get(UserRoute::export_project_data))
+            .layer(Extension(current_user.clone()))
+            .with_state(state.clone());
+        app.oneshot(Request::get(uri).body(Body::empty()).unwrap()).await.unwrap()
+    }
+
+    async fn text(response: Response) -> String {
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn user_export_needs_the_user_manage_permission() {
+        let state = state().await;
+        let user = test_support::insert_user(&state, "user@example.com", json!([])).await;
+        let response = send(&state, &user, "/users/export").await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn user_export_streams_the_filtered_users_as_ndjson() {
+        let state = state().await;
+        let admin = test_support::insert_user(&state, "admin@example.com", json!(["user:manage"])).await;
+        test_support::insert_user(&state, "bob@example.org", json!([])).await;
+        test_support::insert_user(&state, "alice@example.org", json!([])).await;
+
+        let uri = "/users/export?filter[username][contains]=example.org&sort=username";
+        let response = send(&state, &admin, uri).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[CONTENT_TYPE], "application/x-ndjson");
+        assert_eq!(response.headers()[CONTENT_DISPOSITION], "attachment; filename=\"users.ndjson\"");
+        let rows: Vec<Value> =
+            text(response).await.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        let usernames: Vec<&str> = rows.iter().map(|row| row["username"].as_str().unwrap()).collect();
+        assert_eq!(usernames, ["alice@example.org", "bob@example.org"]);
+        assert!(rows.iter().all(|row| row.get("password").is_none()), "{rows:?}");
+    }
+
+    #[tokio::test]
+    async fn user_export_writes_csv_with_a_header_row() {
+        let state = state().await;
+        let admin = test_support::insert_user(&state, "admin@example.com", json!(["user:manage"])).await;
+
+        let response = send(&state, &admin, "/users/export?format=csv").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[CONTENT_DISPOSITION], "attachment; filename=\"users.csv\"");
+        let body = text(response).await;
+        let lines: Vec<&str> = body.lines().collect();
+        assert_eq!(lines.len(), 2, "{body}");
+        assert!(lines[0].starts_with("id,username,"), "{body}");
+        assert!(lines[1].contains("admin@example.com"), "{body}");
+    }
+
+    #[tokio::test]
+    async fn user_export_rejects_unknown_formats() {
+        let state = state().await;
+        let admin = test_support::insert_user(&state, "admin@example.com", json!(["user:manage"])).await;
+        let response = send(&state, &admin, "/users/export?format=xlsx").await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn project_data_export_is_open_to_every_user() {
+        let state = state().await;
+        let user = test_support::insert_user(&state, "user@example.com", json!([])).await;
+
+        let response = send(&state, &user, "/users/me/project-data/export?format=csv").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[CONTENT_TYPE], "text/csv; charset=utf-8");
+        assert_eq!(
+            response.headers()[CONTENT_DISPOSITION],
+            "attachment; filename=\"project-data.csv\""
+        );
+        // Without project data the stream ends without a row or an error trailer
+        assert_eq!(text(response).await, "");
+    }
+}
@@ -1,9 +1,11 @@
 use crate::infrastructure::config::{Config};
 use crate::infrastructure::errors::AppError;
 use crate::route::AppRoute;
+use crate::service::privacy::PrivacyService;
 use anyhow::Context;
 use axum::{serve};
 use sea_orm::{Database, DatabaseConnection};
+use std::net::SocketAddr;
 use std::sync::Arc;
 use tokio::signal;
 use tracing::info;
@@ -24,9 +26,13 @@ impl AppServer {
         info!("server has launched on {local_addr} 🚀");
 
         let db = Arc::new(Self::create_db_conn(&config).await?);
+        PrivacyService::fail_interrupted_exports(db.clone()).await?;
         let router = AppRoute::init(db, config);
 
-        serve(tcp_listener, router)
+        serve(
+            tcp_listener,
+            router.into_make_service_with_connect_info::<SocketAddr>(),
+        )
             .with_graceful_shutdown(Self::shutdown_signal())
             .await
             .context("Failed to start server")?;
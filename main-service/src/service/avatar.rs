@@ -0,0 +1,106 @@
+use crate::infrastructure::errors::{AppError, AppResult, ValidationMessageError};
+use crate::infrastructure::state::AppState;
+use crate::infrastructure::uuid::generate_uuid;
+use crate::infrastructure::validation_code::ValidationCode;
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageDecoder, ImageFormat, ImageReader, Limits};
+use std::io::Cursor;
+use uuid::Uuid;
+
+/// Square renditions stored for every avatar, by name and edge length in pixels.
+pub const AVATAR_SIZES: [(&str, u32); 3] = [("small", 64), ("medium", 256), ("large", 512)];
+pub const AVATAR_CONTENT_TYPES: [&str; 4] = ["image/png", "image/jpeg", "image/webp", "image/gif"];
+const ACCEPTED_FORMATS: [ImageFormat; 4] = [
+    ImageFormat::Png,
+    ImageFormat::Jpeg,
+    ImageFormat::WebP,
+    ImageFormat::Gif,
+];
+const MAX_SOURCE_DIMENSION: u32 = 8192;
+const JPEG_QUALITY: u8 = 85;
+
+pub struct AvatarService;
+
+impl AvatarService {
+    /// Process the uploaded image and store its renditions, returning the new avatar key.
+    pub async fn store(state: &AppState, user_id: &Uuid, bytes: Vec<u8>) -> AppResult<String> {
+        let renditions = tokio::task::spawn_blocking(move || Self::process(&bytes))
+            .await
+            .map_err(|e| AppError::InternalServerErrorWithContext(e.to_string()))??;
+
+        // A fresh key per upload so clients and proxies never serve a stale avatar
+        let key = format!("avatars/{user_id}/{}", generate_uuid());
+        for (name, jpeg) in renditions {
+            state.storage.put(&Self::file_key(&key, name), jpeg).await?;
+        }
+        Ok(key)
+    }
+
+    /// Delete every rendition of a previously stored avatar.
+    pub async fn remove(state: &AppState, key: &str) -> AppResult<()> {
+        for (name, _) in AVATAR_SIZES {
+            state.storage.delete(&Self::file_key(key, name)).await?;
+        }
+        Ok(())
+    }
+
+    pub fn file_key(key: &str, name: &str) -> String {
+        format!("{key}/{name}.jpg")
+    }
+
+    /// Decode, orient, center crop and resize the image, re-encoding every size as JPEG.
+    /// Only pixel data survives re-encoding, so EXIF and other metadata are dropped.
+    pub fn process(bytes: &[u8]) -> AppResult<Vec<(&'static str, Vec<u8>)>> {
+        let mut reader = ImageReader::new(Cursor::new(bytes)).with_guessed_format()?;
+        if !reader.format().is_some_and(|format| ACCEPTED_FORMATS.contains(&format)) {
+            let message = "avatar must be a PNG, JPEG, WebP or GIF image";
+            return Err(Self::invalid(ValidationCode::IMAGE_TYPE, message));
+        }
+        let mut limits = Limits::default();
+        limits.max_image_width = Some(MAX_SOURCE_DIMENSION);
+        limits.max_image_height = Some(MAX_SOURCE_DIMENSION);
+        reader.limits(limits);
+
+        let mut decoder = reader
+            .into_decoder()
+            .map_err(|_| Self::undecodable())?;
+        let orientation = decoder.orientation().ok();
+        let mut image = DynamicImage::from_decoder(decoder)
+            .map_err(|_| Self::undecodable())?;
+        if let Some(orientation) = orientation {
+            image.apply_orientation(orientation);
+        }
+
+        let edge = image.width().min(image.height());
+        if edge == 0 {
+            return Err(Self::undecodable());
+        }
+        let square = image.crop_imm(
+            (image.width() - edge) / 2,
+            (image.height() - edge) / 2,
+            edge,
+            edge,
+        );
+
+        AVATAR_SIZES
+            .iter()
+            .map(|(name, size)| {
+                let resized = square.resize_exact(*size, *size, FilterType::Lanczos3).to_rgb8();
+                let mut jpeg = Vec::new();
+                JpegEncoder::new_with_quality(&mut jpeg, JPEG_QUALITY)
+                    .encode_image(&resized)
+                    .map_err(|e| AppError::InternalServerErrorWithContext(e.to_string()))?;
+                Ok((*name, jpeg))
+            })
+            .collect()
+    }
+
+    fn undecodable() -> AppError {
+        Self::invalid(ValidationCode::IMAGE_UNDECODABLE, "avatar could not be decoded")
+    }
+
+    fn invalid(code: &'static str, message: &str) -> AppError {
+        AppError::ValidationMessageError(ValidationMessageError::new("avatar", code, message))
+    }
+}
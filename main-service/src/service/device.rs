@@ -0,0 +1,51 @@
+use crate::infrastructure::errors::{AppError, AppResult};
+use crate::infrastructure::state::AppState;
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use image::{DynamicImage, ImageFormat, Luma};
+use qrcode::QrCode;
+use std::io::Cursor;
+use uuid::Uuid;
+
+/// Unambiguous characters so the code can still be typed when scanning fails.
+const PAIRING_ALPHABET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+const PAIRING_CODE_LENGTH: usize = 8;
+
+pub struct DevicePairingService;
+
+impl DevicePairingService {
+    /// Create a single use pairing code bound to the user.
+    pub async fn create(state: &AppState, user_id: Uuid) -> String {
+        let code: String = (0..PAIRING_CODE_LENGTH)
+            .map(|_| {
+                let index = OsRng.next_u32() as usize % PAIRING_ALPHABET.len();
+                PAIRING_ALPHABET[index] as char
+            })
+            .collect();
+        state.pairing_cache.insert(code.clone(), user_id).await;
+        code
+    }
+
+    /// Consume the pairing code, returning the user it was issued for.
+    pub async fn redeem(state: &AppState, code: &str) -> AppResult<Uuid> {
+        state
+            .pairing_cache
+            .remove(&code.trim().to_uppercase())
+            .await
+            .ok_or(AppError::BadRequest("pairing code is invalid or expired".to_string()))
+    }
+
+    /// Render the code as a PNG QR image encoded as a data URL.
+    pub fn render_qr(code: &str) -> AppResult<String> {
+        let qr = QrCode::new(code.as_bytes())
+            .map_err(|e| AppError::InternalServerErrorWithContext(e.to_string()))?;
+        let image = qr.render::<Luma<u8>>().min_dimensions(256, 256).build();
+
+        let mut png = Vec::new();
+        DynamicImage::ImageLuma8(image)
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .map_err(|e| AppError::InternalServerErrorWithContext(e.to_string()))?;
+        Ok(format!("data:image/png;base64,{}", STANDARD.encode(png)))
+    }
+}
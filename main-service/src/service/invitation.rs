@@ -0,0 +1,200 @@
+use crate::dto::invitation::AcceptInvitationDto;
+use crate::dto::permission::Permission;
+use crate::infrastructure::errors::{AppError, AppResult, ValidationMessageError};
+use crate::infrastructure::state::AppState;
+use crate::infrastructure::uuid::generate_uuid;
+use crate::infrastructure::validation_code::ValidationCode;
+use crate::repository::invitation::InvitationRepository;
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::Utc;
+use entity::prelude::{UserAccount, UserInvitation};
+use entity::{project, project_participant, user_account, user_invitation};
+use sea_orm::sea_query::Expr;
+use sea_orm::ActiveValue::Set;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, EntityTrait, IntoActiveModel, ModelTrait, QueryFilter,
+    TransactionTrait,
+};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+const TOKEN_BYTES: usize = 32;
+
+pub struct InvitationService;
+
+impl InvitationService {
+    /// A fresh random token and the hash stored in its place.
+    pub fn generate_token() -> (String, String) {
+        let mut bytes = [0u8; TOKEN_BYTES];
+        OsRng.fill_bytes(&mut bytes);
+        let token = URL_SAFE_NO_PAD.encode(bytes);
+        let hash = Self::hash_token(&token);
+        (token, hash)
+    }
+
+    pub fn hash_token(token: &str) -> String {
+        URL_SAFE_NO_PAD.encode(Sha256::digest(token.trim().as_bytes()))
+    }
+
+    pub fn invite_url(state: &AppState, token: &str) -> String {
+        format!("{}?token={token}", state.config.invitation_url)
+    }
+
+    /// User managers may invite anyone, project owners only into their own projects and
+    /// without granting permissions.
+    pub async fn ensure_can_invite(
+        state: &AppState,
+        current_user: &user_account::Model,
+        permissions: &[Permission],
+        project_id: Option<Uuid>,
+    ) -> AppResult<()> {
+        if let Some(project_id) = project_id {
+            let project = Self::get_project(state, project_id).await?;
+            if Permission::UserManage.granted_to(current_user) {
+                return Ok(());
+            }
+            let owner = project.find_related(UserAccount).one(&*state.db).await?;
+            return match owner {
+                Some(owner) if owner.id == current_user.id && permissions.is_empty() => Ok(()),
+                Some(owner) if owner.id == current_user.id => Err(AppError::Forbidden(
+                    "Only user managers can grant permissions".to_string(),
+                )),
+                _ => Err(AppError::Forbidden(
+                    "Only the project owner can invite to this project".to_string(),
+                )),
+            };
+        }
+        Permission::UserManage.ensure(current_user)
+    }
+
+    async fn get_project(state: &AppState, project_id: Uuid) -> AppResult<project::Model> {
+        project::Entity::find_by_id(project_id)
+            .one(&*state.db)
+            .await?
+            .ok_or(AppError::NotFound("project not found".to_string()))
+    }
+
+    /// The inviter and user managers may revoke or reissue an invitation.
+    pub fn ensure_can_manage(
+        current_user: &user_account::Model,
+        invitation: &user_invitation::Model,
+    ) -> AppResult<()> {
+        if invitation.invited_by == current_user.id
+            || Permission::UserManage.granted_to(current_user)
+        {
+            Ok(())
+        } else {
+            Err(AppError::Forbidden("Missing required permission".to_string()))
+        }
+    }
+
+    /// Accept an invitation, creating the account or linking the existing one with the same
+    /// email, and join the invitation's project. Existing accounts prove ownership with their
+    /// current password and keep their permissions, the invitation's only go to new accounts.
+    /// Raising those of an existing account is up to `PUT /users/{id}/permissions`, which
+    /// refuses callers changing their own.
+    pub async fn accept(
+        state: &AppState,
+        payload: AcceptInvitationDto,
+    ) -> AppResult<(user_account::Model, user_invitation::Model)> {
+        let token_hash = Self::hash_token(&payload.token.unwrap_or_default());
+        let password = payload.password.unwrap_or_default();
+        let policy = &state.config.password_policy;
+        let invitation = InvitationRepository::new(state.db.clone())
+            .get_pending_by_token(&token_hash)
+            .await?;
+        if let Some(project_id) = invitation.project_id {
+            Self::get_project(state, project_id).await?;
+        }
+        let granted = Permission::from_json(&invitation.permissions);
+
+        let existing = UserAccount::find()
+            .filter(user_account::Column::Username.eq(&invitation.email))
+            .one(&*state.db)
+            .await?;
+        let new_password_hash = match &existing {
+            Some(user) if user.deleted_at.is_some() || user.disabled_at.is_some() => {
+                return Err(AppError::BadRequest("account is not active".to_string()));
+            }
+            Some(user) => {
+                if !policy.verify(&password, &user.password).await? {
+                    return Err(AppError::ValidationMessageError(ValidationMessageError::new(
+                        "password",
+                        ValidationCode::PASSWORD_INCORRECT,
+                        "password is incorrect",
+                    )));
+                }
+                None
+            }
+            None => {
+                policy.check_strength(&password, Some(&invitation.email))?;
+                Some(policy.hash(&password).await?)
+            }
+        };
+
+        let txn = state.db.begin().await?;
+        // Claiming the row first makes the token single use even under concurrent accepts
+        let claimed = UserInvitation::update_many()
+            .col_expr(user_invitation::Column::AcceptedAt, Expr::value(Utc::now().fixed_offset()))
+            .filter(user_invitation::Column::Id.eq(invitation.id))
+            .filter(user_invitation::Column::AcceptedAt.is_null())
+            .filter(user_invitation::Column::RevokedAt.is_null())
+            .exec(&txn)
+            .await?;
+        if claimed.rows_affected == 0 {
+            return Err(AppError::BadRequest("invitation is invalid or expired".to_string()));
+        }
+
+        let user = match (existing, new_password_hash) {
+            (Some(user), _) => {
+                if !granted.is_empty() {
+                    tracing::info!(
+                        "invitation {} accepted by existing user {}, its permissions were not granted",
+                        invitation.id,
+                        user.id
+                    );
+                }
+                user
+            }
+            (None, password_hash) => {
+                let user = user_account::ActiveModel {
+                    id: Set(generate_uuid()),
+                    username: Set(invitation.email.clone()),
+                    password: Set(password_hash.unwrap_or_default()),
+                    display_name: Set(payload.display_name),
+                    permissions: Set(serde_json::to_value(&granted)?),
+                    ..Default::default()
+                };
+                user.insert(&txn).await?
+            }
+        };
+
+        if let Some(project_id) = invitation.project_id {
+            let participating = project_participant::Entity::find()
+                .filter(project_participant::Column::ProjectId.eq(project_id))
+                .filter(project_participant::Column::UserId.eq(user.id))
+                .one(&txn)
+                .await?;
+            if participating.is_none() {
+                let participant = project_participant::ActiveModel {
+                    id: Set(generate_uuid()),
+                    project_id: Set(project_id),
+                    user_id: Set(user.id),
+                    ..Default::default()
+                };
+                participant.insert(&txn).await?;
+            }
+        }
+
+        let mut accepted = invitation.into_active_model();
+        accepted.accepted_user_id = Set(Some(user.id));
+        let invitation = accepted.update(&txn).await?;
+        txn.commit().await?;
+
+        state.user_cache.invalidate(&user.id).await;
+        tracing::info!("invitation {} accepted by user {}", invitation.id, user.id);
+        Ok((user, invitation))
+    }
+}
@@ -0,0 +1,5 @@
+pub mod avatar;
+pub mod device;
+pub mod invitation;
+pub mod privacy;
+pub mod token;
\ No newline at end of file
@@ -0,0 +1,273 @@
+use crate::dto::privacy::{DataExportArchive, ExportedFile};
+use crate::infrastructure::errors::{AppError, AppResult};
+use crate::infrastructure::state::AppState;
+use crate::repository::data_export::DataExportRepository;
+use crate::service::avatar::{AvatarService, AVATAR_SIZES};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chrono::Utc;
+use entity::prelude::{ImpersonationAudit, UserAccount, UserInvitation, UserSession};
+use entity::{
+    data_export, impersonation_audit, project, project_data, project_data_image,
+    project_participant, user_account, user_invitation, user_session,
+};
+use sea_orm::sea_query::Expr;
+use sea_orm::ActiveValue::Set;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, Identity, IntoActiveModel,
+    ModelTrait, QueryFilter, RelationDef, RelationTrait, TransactionTrait,
+};
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+const ARCHIVE_FORMAT_VERSION: u32 = 1;
+const EXPORT_SWEEP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const EXPORT_FAILED_MESSAGE: &str = "export could not be generated, please try again";
+/// Project images can run into gigabytes, far too much to inline as base64 in a JSON archive.
+const PROJECT_IMAGES_EXCLUDED: &str =
+    "project_data_images lists the metadata of your project images, the image files themselves are not included";
+
+/// Data subject requests: exporting and erasing everything tied to a `user_account`.
+pub struct PrivacyService;
+
+impl PrivacyService {
+    /// Queue an export and build its archive in the background.
+    pub async fn request_export(state: &AppState, user_id: Uuid) -> AppResult<data_export::Model> {
+        Self::purge_expired_exports(state, Some(&user_id)).await?;
+        let export = DataExportRepository::new(state.db.clone())
+            .create(user_id)
+            .await?;
+
+        let state = state.clone();
+        let export_id = export.id;
+        tokio::spawn(async move { Self::run_export(&state, export_id, user_id).await });
+        Ok(export)
+    }
+
+    async fn run_export(state: &AppState, export_id: Uuid, user_id: Uuid) {
+        let repository = DataExportRepository::new(state.db.clone());
+        let result = async {
+            repository.mark_running(&export_id).await?;
+            let archive = Self::build_archive(state, &user_id).await?;
+            let key = format!("exports/{user_id}/{export_id}.json");
+            state
+                .private_storage
+                .put(&key, serde_json::to_vec_pretty(&archive)?)
+                .await?;
+            repository
+                .complete(&export_id, key, state.config.data_export_ttl)
+                .await
+        }
+        .await;
+
+        if let Err(e) = result {
+            tracing::error!("data export {export_id} failed: {e}");
+            if let Err(e) = repository.fail(&export_id, EXPORT_FAILED_MESSAGE.to_string()).await {
+                tracing::error!("failed to record failure of data export {export_id}: {e}");
+            }
+        }
+    }
+
+    pub async fn build_archive(state: &AppState, user_id: &Uuid) -> AppResult<DataExportArchive> {
+        let db = &*state.db;
+        let user = UserAccount::find_by_id(*user_id)
+            .one(db)
+            .await?
+            .ok_or(AppError::NotFound("user not found".to_string()))?;
+
+        let mut profile = serde_json::to_value(&user)?;
+        if let Some(profile) = profile.as_object_mut() {
+            profile.remove("password");
+        }
+
+        let mut files = Vec::new();
+        if let Some(key) = &user.avatar_key {
+            for (name, _) in AVATAR_SIZES {
+                let key = AvatarService::file_key(key, name);
+                files.push(ExportedFile {
+                    data: STANDARD.encode(state.storage.get(&key).await?),
+                    content_type: "image/jpeg".to_string(),
+                    key,
+                });
+            }
+        }
+
+        Ok(DataExportArchive {
+            format_version: ARCHIVE_FORMAT_VERSION,
+            generated_at: Utc::now().fixed_offset(),
+            profile,
+            sessions: user.find_related(UserSession).into_json().all(db).await?,
+            impersonations: ImpersonationAudit::find()
+                .filter(impersonation_audit::Column::TargetUserId.eq(user.id))
+                .into_json()
+                .all(db)
+                .await?,
+            projects: user.find_related(project::Entity).into_json().all(db).await?,
+            project_participants: user
+                .find_related(project_participant::Entity)
+                .into_json()
+                .all(db)
+                .await?,
+            project_data: user.find_related(project_data::Entity).into_json().all(db).await?,
+            project_data_images: user
+                .find_related(project_data_image::Entity)
+                .into_json()
+                .all(db)
+                .await?,
+            files,
+            excluded: vec![PROJECT_IMAGES_EXCLUDED.to_string()],
+        })
+    }
+
+    /// Exports are built by tasks of this process, those still pending or running at startup
+    /// were interrupted by a restart. Must run before requests are served, and assumes a single
+    /// instance of the app per database.
+    pub async fn fail_interrupted_exports(db: Arc<DatabaseConnection>) -> AppResult<()> {
+        let failed = DataExportRepository::new(db)
+            .fail_interrupted(EXPORT_FAILED_MESSAGE)
+            .await?;
+        if failed > 0 {
+            tracing::warn!("marked {failed} data exports interrupted by a restart as failed");
+        }
+        Ok(())
+    }
+
+    /// Purge expired archives of every user for as long as the app runs.
+    pub fn spawn_export_sweep(state: &AppState) {
+        let state = state.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(EXPORT_SWEEP_INTERVAL);
+            loop {
+                interval.tick().await;
+                if let Err(e) = Self::purge_expired_exports(&state, None).await {
+                    tracing::error!("failed to purge expired data exports: {e}");
+                }
+            }
+        });
+    }
+
+    /// Drop archives past their retention period, of one user or of everyone.
+    async fn purge_expired_exports(state: &AppState, user_id: Option<&Uuid>) -> AppResult<()> {
+        let repository = DataExportRepository::new(state.db.clone());
+        for export in repository.list_expired(user_id).await? {
+            if let Some(key) = &export.file_key {
+                state.private_storage.delete(key).await?;
+            }
+            repository.delete(&export.id).await?;
+        }
+        Ok(())
+    }
+
+    /// Erase the user's personal data. The account row is anonymized rather than deleted so
+    /// audits and content owned by it keep valid references. With `purge_content` the user's
+    /// projects, submissions and images are deleted as well.
+    pub async fn erase(state: &AppState, user_id: &Uuid, purge_content: bool) -> AppResult<()> {
+        let user = UserAccount::find_by_id(*user_id)
+            .one(&*state.db)
+            .await?
+            .ok_or(AppError::NotFound("user not found".to_string()))?;
+        let sessions = user.find_related(UserSession).all(&*state.db).await?;
+        let exports = user.find_related(data_export::Entity).all(&*state.db).await?;
+
+        let txn = state.db.begin().await?;
+        if purge_content {
+            // Children before parents so foreign keys never dangle
+            Self::delete_owned::<project_data_image::Entity>(
+                &txn,
+                user_account::Relation::ProjectDataImage.def(),
+                user.id,
+            )
+            .await?;
+            Self::delete_owned::<project_data::Entity>(
+                &txn,
+                user_account::Relation::ProjectData.def(),
+                user.id,
+            )
+            .await?;
+            Self::delete_owned::<project_participant::Entity>(
+                &txn,
+                user_account::Relation::ProjectParticipant.def(),
+                user.id,
+            )
+            .await?;
+            Self::delete_owned::<project::Entity>(
+                &txn,
+                user_account::Relation::Project.def(),
+                user.id,
+            )
+            .await?;
+        }
+        Self::delete_owned::<user_session::Entity>(
+            &txn,
+            user_account::Relation::UserSession.def(),
+            user.id,
+        )
+        .await?;
+        Self::delete_owned::<data_export::Entity>(
+            &txn,
+            user_account::Relation::DataExport.def(),
+            user.id,
+        )
+        .await?;
+        // Invitations addressed to the user carry their email
+        UserInvitation::delete_many()
+            .filter(user_invitation::Column::Email.eq(&user.username))
+            .exec(&txn)
+            .await?;
+
+        let now = Utc::now().fixed_offset();
+        let avatar_key = user.avatar_key.clone();
+        let deleted_at = user.deleted_at.unwrap_or(now);
+        let mut account = user.into_active_model();
+        account.username = Set(format!("erased-{user_id}"));
+        // Not a valid PHC string, so no password can ever match again
+        account.password = Set("!".to_string());
+        account.permissions = Set(serde_json::json!([]));
+        account.display_name = Set(None);
+        account.locale = Set(None);
+        account.timezone = Set(None);
+        account.avatar_key = Set(None);
+        account.disabled_at = Set(Some(now));
+        account.deleted_at = Set(Some(deleted_at));
+        account.update(&txn).await?;
+        txn.commit().await?;
+
+        state.user_cache.invalidate(user_id).await;
+        for session in &sessions {
+            state.session_cache.invalidate(&session.id).await;
+        }
+
+        // Rows are gone, leftover files are unreachable and only logged
+        if let Some(key) = avatar_key {
+            if let Err(e) = AvatarService::remove(state, &key).await {
+                tracing::warn!("failed to delete avatar {key} of erased user {user_id}: {e}");
+            }
+        }
+        for key in exports.iter().filter_map(|export| export.file_key.as_deref()) {
+            if let Err(e) = state.private_storage.delete(key).await {
+                tracing::warn!("failed to delete export {key} of erased user {user_id}: {e}");
+            }
+        }
+        tracing::info!("erased user {user_id} (purge_content: {purge_content})");
+        Ok(())
+    }
+
+    /// Delete the rows of `E` that reference the user through the given `has_many` relation.
+    async fn delete_owned<E: EntityTrait>(
+        db: &impl ConnectionTrait,
+        relation: RelationDef,
+        user_id: Uuid,
+    ) -> AppResult<u64> {
+        let Identity::Unary(column) = relation.to_col else {
+            return Err(AppError::InternalServerErrorWithContext(
+                "composite user references are not supported".to_string(),
+            ));
+        };
+        let result = E::delete_many()
+            .filter(Expr::col(column).eq(user_id))
+            .exec(db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+}
@@ -0,0 +1,41 @@
+use crate::dto::auth::{ActorClaims, Claims, OAuth2Response};
+use crate::extractor::client::ClientInfo;
+use crate::infrastructure::errors::AppResult;
+use crate::infrastructure::state::AppState;
+use crate::middleware::auth::encode_jwt;
+use crate::repository::session::SessionRepository;
+use chrono::TimeDelta;
+use uuid::Uuid;
+
+/// Optional claims narrowing what the issued token stands for.
+#[derive(Default)]
+pub struct TokenOptions {
+    pub act: Option<ActorClaims>,
+    pub scope: Option<String>,
+}
+
+pub struct TokenService;
+
+impl TokenService {
+    /// Record a new session for the user and sign a bearer token bound to it.
+    pub async fn issue(
+        state: &AppState,
+        user_id: Uuid,
+        client: &ClientInfo,
+        expires_in: TimeDelta,
+        options: TokenOptions,
+    ) -> AppResult<OAuth2Response> {
+        let session = SessionRepository::new(state.db.clone())
+            .create(user_id, client, expires_in)
+            .await?;
+
+        let mut claims = Claims::new(user_id.to_string(), session.id.to_string(), expires_in);
+        claims.act = options.act;
+        claims.scope = options.scope.clone();
+        let token = encode_jwt(&claims, &state.config.jwt_secret)?;
+
+        let mut response = OAuth2Response::new_bearer(token, expires_in);
+        response.scope = options.scope;
+        Ok(response)
+    }
+}
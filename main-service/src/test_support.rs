@@ -0,0 +1,72 @@
+//! Shared setup of tests that need an `AppState`.
+
+use crate::infrastructure::config::{Config, ConfigSources};
+use crate::infrastructure::state::AppState;
+use sea_orm::DatabaseConnection;
+use std::sync::Arc;
+
+pub const JWT_SECRET: &str = "ddca7ed3f8d15c04ac4b96623120172f14b470b3b5027857056a74f03023fe75";
+
+/// Only checked against the schemes of the build, `state` never connects to it.
+const DATABASE_URL: &str = match cfg!(feature = "sqlite") {
+    true => "sqlite::memory:",
+    false => "postgres://app@db/app",
+};
+
+pub fn config() -> Arc<Config> {
+    let overrides = [
+        ("database_url".to_string(), DATABASE_URL.to_string()),
+        ("jwt_secret".to_string(), JWT_SECRET.to_string()),
+    ];
+    let config = ConfigSources::new(None, &overrides).and_then(|sources| sources.load(None));
+    Arc::new(config.unwrap())
+}
+
+/// State without a database, for code that never reaches it.
+pub fn state() -> AppState {
+    AppState::init(Arc::new(DatabaseConnection::Disconnected), config())
+}
+
+/// State on an in-memory SQLite database holding the tables `tables` creates, e.g.
+/// `sqlite_state(|schema| vec![schema.create_table_from_entity(user_account::Entity)])`.
+#[cfg(feature = "sqlite")]
+pub async fn sqlite_state(
+    tables: impl FnOnce(&sea_orm::Schema) -> Vec<sea_orm::sea_query::TableCreateStatement>,
+) -> AppState {
+    use sea_orm::{ConnectionTrait, Database, DbBackend, Schema};
+
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    for table in tables(&Schema::new(DbBackend::Sqlite)) {
+        db.execute(DbBackend::Sqlite.build(&table)).await.unwrap();
+    }
+    AppState::init(Arc::new(db), config())
+}
+
+/// Insert an account holding `permissions`, e.g. `json!(["user:manage"])`.
+#[cfg(feature = "sqlite")]
+pub async fn insert_user(
+    state: &AppState,
+    username: &str,
+    permissions: serde_json::Value,
+) -> entity::user_account::Model {
+    use sea_orm::{ActiveModelTrait, IntoActiveModel};
+
+    let user = entity::user_account::Model {
+        id: uuid::Uuid::now_v7(),
+        username: username.to_string(),
+        password: String::new(),
+        created_at: chrono::Utc::now().into(),
+        deleted_at: None,
+        permissions,
+        disabled_at: None,
+        display_name: None,
+        locale: None,
+        timezone: None,
+        avatar_key: None,
+        phone: None,
+        accent_color: None,
+        map_extent: None,
+        location: None,
+    };
+    user.into_active_model().reset_all().insert(&*state.db).await.unwrap()
+}